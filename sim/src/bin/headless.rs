@@ -0,0 +1,226 @@
+//! Runs the simulation without a window or GPU: renders scenes to PNG files,
+//! records them as GIFs or PNG sequences and checks them against the golden
+//! images in `sim/golden`.
+//!
+//! ```text
+//! pixelgame-headless render <scene> <out.png> [options]
+//! pixelgame-headless record <scene> <out.gif | out-dir> [options] [--every N]
+//! pixelgame-headless golden [--bless]
+//! ```
+//!
+//! Options are `--ticks N` to run for another number of ticks than the
+//! scene's own, `--scale N` for pixels per cell, `--crop X0,Y0,X1,Y1` to
+//! draw only part of the grid, `--view NAME` to color cells by density,
+//! temperature, moisture, velocity, element or what changed, and `--active`,
+//! `--grid` and `--legend` for overlays. Recordings capture every Nth tick,
+//! every tick by default.
+//!
+//! `golden` fails if any scene no longer matches its image, writing what it
+//! got next to it as `<scene>.actual.png`. `--bless` overwrites the golden
+//! images instead, after a deliberate change to how things look or behave.
+
+use std::process::ExitCode;
+
+use pixelgame_sim::{
+    chunks::Rect,
+    image::{Image, Overlays},
+    recording::{RecordOptions, Recorder},
+    scenes::{Scene, GOLDEN_DIR, SCALE, SCENES},
+    view::View,
+    world::World,
+};
+
+const USAGE: &str = "usage:
+  pixelgame-headless render <scene> <out.png> [options]
+  pixelgame-headless record <scene> <out.gif | out-dir> [options] [--every N]
+  pixelgame-headless golden [--bless]
+options: --ticks N, --scale N, --crop X0,Y0,X1,Y1, --view NAME, --active, --grid, --legend";
+
+/// What to run and how to draw it, parsed from the command line.
+struct Args<'a> {
+    scene: &'static Scene,
+    out: &'a str,
+    ticks: usize,
+    every: usize,
+    scale: usize,
+    crop: Option<Rect>,
+    view: View,
+    overlays: Overlays,
+}
+
+impl<'a> Args<'a> {
+    /// Parses `<scene> <out> [options]`, allowing `--every` if `recording`.
+    fn parse(args: &'a [String], recording: bool) -> Result<Self, String> {
+        let [scene, out, options @ ..] = args else {
+            return Err(USAGE.to_string());
+        };
+        let scene = Scene::find(scene)?;
+        let mut parsed = Args {
+            scene,
+            out,
+            ticks: scene.ticks,
+            every: 1,
+            scale: SCALE,
+            crop: None,
+            view: View::Normal,
+            overlays: Overlays::empty(),
+        };
+
+        let mut options = options.iter();
+        while let Some(option) = options.next() {
+            let mut value = || {
+                options
+                    .next()
+                    .ok_or_else(|| format!("{option} needs a value"))
+            };
+            match option.as_str() {
+                "--ticks" => parsed.ticks = parse_count(value()?, 0)?,
+                "--every" if recording => parsed.every = parse_count(value()?, 1)?,
+                "--scale" => parsed.scale = parse_count(value()?, 1)?,
+                "--crop" => parsed.crop = Some(parse_crop(value()?, scene)?),
+                "--active" => parsed.overlays |= Overlays::ACTIVE_CHUNKS,
+                "--view" => parsed.view = parse_view(value()?)?,
+                "--grid" => parsed.overlays |= Overlays::CHUNK_GRID,
+                "--legend" => parsed.overlays |= Overlays::LEGEND,
+                _ => return Err(format!("unknown option `{option}`\n{USAGE}")),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn image(&self, world: &World) -> Image {
+        match self.crop {
+            Some(region) => Image::of_region(world, region, self.scale, self.view, self.overlays),
+            None => Image::of_world(world, self.scale, self.view, self.overlays),
+        }
+    }
+}
+
+fn parse_count(arg: &str, min: usize) -> Result<usize, String> {
+    arg.parse()
+        .ok()
+        .filter(|&n| n >= min)
+        .ok_or_else(|| format!("invalid number `{arg}`, expected at least {min}"))
+}
+
+fn parse_view(arg: &str) -> Result<View, String> {
+    View::from_name(arg).ok_or_else(|| {
+        let names: Vec<_> = View::ALL.iter().map(|view| view.name()).collect();
+        format!(
+            "unknown view `{arg}`, expected one of: {}",
+            names.join(", ")
+        )
+    })
+}
+
+/// Parses `X0,Y0,X1,Y1`, which must be a non-empty part of the scene.
+fn parse_crop(arg: &str, scene: &Scene) -> Result<Rect, String> {
+    let invalid = || {
+        format!(
+            "invalid crop `{arg}`, expected X0,Y0,X1,Y1 within {}x{}",
+            scene.width, scene.height
+        )
+    };
+    let numbers = arg
+        .split(',')
+        .map(|n| n.trim().parse())
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|_| invalid())?;
+    let &[x0, y0, x1, y1] = numbers.as_slice() else {
+        return Err(invalid());
+    };
+    let rect = Rect { x0, y0, x1, y1 };
+    if rect.is_empty() || x1 > scene.width || y1 > scene.height {
+        return Err(invalid());
+    }
+    Ok(rect)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("render") => render(&args[1..]),
+        Some("record") => record(&args[1..]),
+        Some("golden") => match &args[1..] {
+            [] => golden(false),
+            [flag] if flag == "--bless" => golden(true),
+            _ => Err(USAGE.to_string()),
+        },
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Renders one scene to a PNG file.
+fn render(args: &[String]) -> Result<ExitCode, String> {
+    let args = Args::parse(args, false)?;
+    let world = args.scene.run(args.ticks);
+    args.image(&world)
+        .save_png(args.out)
+        .map_err(|err| format!("{}: {err}", args.out))?;
+    println!("wrote {}", args.out);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Records one scene, from how it's built until it has run all its ticks.
+fn record(args: &[String]) -> Result<ExitCode, String> {
+    let args = Args::parse(args, true)?;
+    let options = RecordOptions {
+        every: args.every,
+        scale: args.scale,
+        crop: args.crop,
+        view: args.view,
+        overlays: args.overlays,
+        ..RecordOptions::default()
+    };
+    let error = |err| format!("{}: {err}", args.out);
+    let mut recorder = Recorder::new(args.out, options).map_err(error)?;
+    let mut world = args.scene.build();
+    recorder.record(&world).map_err(error)?;
+    for _ in 0..args.ticks {
+        world.update(0.0);
+        recorder.record(&world).map_err(error)?;
+    }
+    let frames = recorder.finish().map_err(error)?;
+    println!("wrote {frames} frames to {}", args.out);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Checks every scene against its golden image, or rewrites them all.
+fn golden(bless: bool) -> Result<ExitCode, String> {
+    if bless {
+        std::fs::create_dir_all(GOLDEN_DIR).map_err(|err| format!("{GOLDEN_DIR}: {err}"))?;
+    }
+    let mut failed = 0;
+    for scene in SCENES {
+        if bless {
+            let path = scene.golden_path();
+            let image = scene.image();
+            image
+                .save_png(&path)
+                .map_err(|err| format!("{}: {err}", path.display()))?;
+            println!("{}: blessed", scene.name);
+            continue;
+        }
+        match scene.check_golden() {
+            Ok(()) => println!("{}: ok", scene.name),
+            Err(problem) => {
+                failed += 1;
+                println!("{}: FAILED, {problem}", scene.name);
+            }
+        }
+    }
+
+    if failed > 0 {
+        println!("{failed} of {} scenes failed", SCENES.len());
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,387 @@
+//! Splitting the grid into chunks that passes update in parallel, and
+//! tracking which of their cells may change so settled ones can sleep.
+
+use std::{
+    marker::PhantomData,
+    ops::{Index, IndexMut, Range},
+};
+
+use rand::SeedableRng;
+use rayon::prelude::*;
+
+use crate::{elements::Cell, world::SimRng};
+
+/// Side length, in cells, of the square chunks the grid is updated in.
+pub const CHUNK_SIZE: usize = 64;
+
+/// How far, in cells, a pass may reach outside the chunk it updates.
+/// Chunks that run at the same time are a whole chunk apart, so with half a
+/// chunk each they never touch the same cells.
+pub const CHUNK_REACH: usize = CHUNK_SIZE / 2;
+
+/// A rectangle of cells, `x0..x1` by `y0..y1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl Rect {
+    pub const EMPTY: Rect = Rect {
+        x0: 0,
+        y0: 0,
+        x1: 0,
+        y1: 0,
+    };
+
+    pub fn is_empty(&self) -> bool {
+        self.x0 >= self.x1 || self.y0 >= self.y1
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x0..self.x1).contains(&x) && (self.y0..self.y1).contains(&y)
+    }
+
+    /// The smallest rectangle holding both.
+    pub fn union(self, other: Rect) -> Rect {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return self;
+        }
+        Rect {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
+    /// The cells in both, or an empty rectangle.
+    pub fn intersect(self, other: Rect) -> Rect {
+        let rect = Rect {
+            x0: self.x0.max(other.x0),
+            y0: self.y0.max(other.y0),
+            x1: self.x1.min(other.x1),
+            y1: self.y1.min(other.y1),
+        };
+        if rect.is_empty() {
+            Rect::EMPTY
+        } else {
+            rect
+        }
+    }
+
+    /// The rectangle with a border of one cell added, cut off at the edges
+    /// of a `w`×`h` grid.
+    pub fn grown(self, w: usize, h: usize) -> Rect {
+        if self.is_empty() {
+            return self;
+        }
+        Rect {
+            x0: self.x0.saturating_sub(1),
+            y0: self.y0.saturating_sub(1),
+            x1: (self.x1 + 1).min(w),
+            y1: (self.y1 + 1).min(h),
+        }
+    }
+
+    /// The cell at `(x, y)` and its 8 neighbours, cut off at the edges of a
+    /// `w`×`h` grid.
+    pub fn around(x: usize, y: usize, w: usize, h: usize) -> Rect {
+        Rect {
+            x0: x.saturating_sub(1),
+            y0: y.saturating_sub(1),
+            x1: (x + 2).min(w),
+            y1: (y + 2).min(h),
+        }
+    }
+}
+
+/// Which cells of each chunk may change, so passes can skip the rest.
+/// Whatever changes wakes the cells around it for the next tick; a chunk
+/// with nothing awake sleeps.
+#[derive(Clone, Debug)]
+pub struct ChunkActivity {
+    w: usize,
+    h: usize,
+    chunks_w: usize,
+    /// Cells each chunk updates this tick, empty while it sleeps.
+    active: Vec<Rect>,
+    /// Cells woken so far for the next tick.
+    woken: Vec<Rect>,
+}
+
+impl ChunkActivity {
+    /// Tracks a `w`×`h` grid, starting with every cell awake.
+    pub fn new(w: usize, h: usize) -> Self {
+        let (chunks_w, chunks_h) = (w.div_ceil(CHUNK_SIZE), h.div_ceil(CHUNK_SIZE));
+        let mut activity = ChunkActivity {
+            w,
+            h,
+            chunks_w,
+            active: vec![Rect::EMPTY; chunks_w * chunks_h],
+            woken: vec![Rect::EMPTY; chunks_w * chunks_h],
+        };
+        activity.wake_all();
+        activity
+    }
+
+    /// Wakes the cells in `rect` for the next tick.
+    pub fn wake(&mut self, rect: Rect) {
+        let rect = rect.intersect(Rect {
+            x0: 0,
+            y0: 0,
+            x1: self.w,
+            y1: self.h,
+        });
+        if rect.is_empty() {
+            return;
+        }
+        for cy in rect.y0 / CHUNK_SIZE..=(rect.y1 - 1) / CHUNK_SIZE {
+            for cx in rect.x0 / CHUNK_SIZE..=(rect.x1 - 1) / CHUNK_SIZE {
+                let i = cy * self.chunks_w + cx;
+                self.woken[i] = self.woken[i].union(rect.intersect(self.bounds(cx, cy)));
+            }
+        }
+    }
+
+    /// Wakes the cell at `(x, y)` and its neighbours for the next tick.
+    pub fn wake_cell(&mut self, x: usize, y: usize) {
+        self.wake(Rect::around(x, y, self.w, self.h));
+    }
+
+    pub fn wake_all(&mut self) {
+        for i in 0..self.woken.len() {
+            self.woken[i] = self.bounds(i % self.chunks_w, i / self.chunks_w);
+        }
+    }
+
+    /// Starts a tick: the cells woken so far become the active ones.
+    pub fn advance(&mut self) {
+        std::mem::swap(&mut self.active, &mut self.woken);
+        self.woken.fill(Rect::EMPTY);
+    }
+
+    /// The cells updated this tick, one rectangle per chunk that is awake.
+    pub fn active(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.active.iter().copied().filter(|rect| !rect.is_empty())
+    }
+
+    /// The cells updated this tick or woken so far for the next one, one
+    /// rectangle inside each chunk that has any.
+    pub fn awake(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.active
+            .iter()
+            .zip(&self.woken)
+            .map(|(&active, &woken)| active.union(woken))
+            .filter(|rect| !rect.is_empty())
+    }
+
+    /// The cells that may have changed this tick, one rectangle per chunk:
+    /// those updated and their neighbours, which passes may write to as
+    /// well, plus those woken by changes for the next tick.
+    pub fn changed(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.active
+            .iter()
+            .zip(&self.woken)
+            .map(|(&active, &woken)| active.grown(self.w, self.h).union(woken))
+            .filter(|rect| !rect.is_empty())
+    }
+
+    /// The cells of chunk `(cx, cy)`.
+    fn bounds(&self, cx: usize, cy: usize) -> Rect {
+        Rect {
+            x0: cx * CHUNK_SIZE,
+            y0: cy * CHUNK_SIZE,
+            x1: ((cx + 1) * CHUNK_SIZE).min(self.w),
+            y1: ((cy + 1) * CHUNK_SIZE).min(self.h),
+        }
+    }
+}
+
+/// Mutable access to the cells of one chunk and those within
+/// [`CHUNK_REACH`] around it, indexed like the full grid. Touching a cell
+/// outside that area panics.
+pub struct Chunk<'a> {
+    cells: *mut Cell,
+    w: usize,
+    h: usize,
+    /// `2^64 / w` rounded up, to divide indices by `w` with a multiplication.
+    /// Kept as `u128` since it's `2^64` itself when `w` is 1.
+    w_reciprocal: u128,
+    xs: Range<usize>,
+    ys: Range<usize>,
+    reach_xs: Range<usize>,
+    reach_ys: Range<usize>,
+    /// Cells woken by this chunk's changes.
+    woken: Rect,
+    /// Cells every chunk updates this tick.
+    active: &'a [Rect],
+    chunks_w: usize,
+    _cells: PhantomData<&'a mut [Cell]>,
+}
+
+impl Chunk<'_> {
+    /// Columns of the cells the pass should update.
+    pub fn xs(&self) -> Range<usize> {
+        self.xs.clone()
+    }
+
+    /// Rows of the cells the pass should update.
+    pub fn ys(&self) -> Range<usize> {
+        self.ys.clone()
+    }
+
+    /// Indices of the cells the pass should update, row by row.
+    pub fn indices(&self) -> impl Iterator<Item = usize> {
+        let (w, xs) = (self.w, self.xs());
+        self.ys()
+            .flat_map(move |y| xs.clone().map(move |x| y * w + x))
+    }
+
+    /// Whether the pass updates the cell at `(x, y)` this tick, as part of
+    /// this chunk or another one.
+    pub fn is_active(&self, x: usize, y: usize) -> bool {
+        self.active[(y / CHUNK_SIZE) * self.chunks_w + x / CHUNK_SIZE].contains(x, y)
+    }
+
+    /// Swaps two cells and wakes the cells around both.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.check(a);
+        self.check(b);
+        // SAFETY: both cells are in reach, which no other chunk touches
+        unsafe { std::ptr::swap(self.cells.add(a), self.cells.add(b)) }
+        self.wake(a);
+        self.wake(b);
+    }
+
+    /// Wakes the cell at index `i` and its neighbours for the next tick.
+    /// Passes call this when they replace a cell, or when a cell could
+    /// have changed but chance decided against it this time.
+    pub fn wake(&mut self, i: usize) {
+        let (x, y) = self.check(i);
+        self.woken = self.woken.union(Rect::around(x, y, self.w, self.h));
+    }
+
+    /// Returns the coordinates of the cell at index `i`.
+    #[inline]
+    fn check(&self, i: usize) -> (usize, usize) {
+        // exact for indices and widths below 2^32, see Lemire et al.,
+        // "Faster Remainder by Direct Computation"
+        let y = ((self.w_reciprocal * i as u128) >> 64) as usize;
+        let x = i.wrapping_sub(y * self.w);
+        if !(self.reach_xs.contains(&x) && self.reach_ys.contains(&y)) {
+            self.out_of_reach(x, y);
+        }
+        (x, y)
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn out_of_reach(&self, x: usize, y: usize) -> ! {
+        panic!(
+            "cell ({x}, {y}) is out of reach of the chunk at ({}, {})",
+            self.xs.start, self.ys.start,
+        );
+    }
+}
+
+impl Index<usize> for Chunk<'_> {
+    type Output = Cell;
+
+    fn index(&self, i: usize) -> &Cell {
+        self.check(i);
+        // SAFETY: the cell is in reach, which no other chunk touches
+        unsafe { &*self.cells.add(i) }
+    }
+}
+
+impl IndexMut<usize> for Chunk<'_> {
+    fn index_mut(&mut self, i: usize) -> &mut Cell {
+        self.check(i);
+        // SAFETY: the cell is in reach, which no other chunk touches
+        unsafe { &mut *self.cells.add(i) }
+    }
+}
+
+/// Pointer to the grid that chunk updates share across threads.
+struct Grid(*mut Cell);
+
+impl Grid {
+    fn cells(&self) -> *mut Cell {
+        self.0
+    }
+}
+
+// SAFETY: chunks built from it only touch cells no other running chunk does
+unsafe impl Sync for Grid {}
+
+/// Runs `pass` on every chunk of the grid in four phases, by the parity of
+/// the chunk's column and row. Chunks in the same phase are at least one
+/// chunk apart, so they run in parallel if `parallel` is set. The pass
+/// only sees the active cells of each chunk, and sleeping chunks are
+/// skipped.
+///
+/// Each chunk gets its own generator, seeded from `seed` with the chunk's
+/// number as the stream, so the result is the same with or without
+/// threads.
+pub fn update_chunks<F>(
+    cells: &mut [Cell],
+    activity: &mut ChunkActivity,
+    seed: u64,
+    parallel: bool,
+    pass: F,
+) where
+    F: Fn(&mut Chunk, &mut SimRng) + Sync,
+{
+    let (w, h, chunks_w) = (activity.w, activity.h, activity.chunks_w);
+    assert_eq!(cells.len(), w * h, "grid size doesn't match");
+    assert!(cells.len() <= u32::MAX as usize, "grid is too large");
+    let chunks_h = h.div_ceil(CHUNK_SIZE);
+    let grid = Grid(cells.as_mut_ptr());
+    let tracked = &*activity;
+
+    let run = |(cx, cy): (usize, usize)| {
+        let bounds = tracked.bounds(cx, cy);
+        let cells = tracked.active[cy * chunks_w + cx];
+        let mut chunk = Chunk {
+            cells: grid.cells(),
+            w,
+            h,
+            w_reciprocal: u64::MAX as u128 / w as u128 + 1,
+            xs: cells.x0..cells.x1,
+            ys: cells.y0..cells.y1,
+            reach_xs: bounds.x0.saturating_sub(CHUNK_REACH)..(bounds.x1 + CHUNK_REACH).min(w),
+            reach_ys: bounds.y0.saturating_sub(CHUNK_REACH)..(bounds.y1 + CHUNK_REACH).min(h),
+            woken: Rect::EMPTY,
+            active: &tracked.active,
+            chunks_w,
+            _cells: PhantomData,
+        };
+        let mut rng = SimRng::seed_from_u64(seed);
+        rng.set_stream((cy * chunks_w + cx) as u64);
+        pass(&mut chunk, &mut rng);
+        chunk.woken
+    };
+
+    let mut woken = Vec::new();
+    for phase in 0..4 {
+        let chunks = (phase / 2..chunks_h)
+            .step_by(2)
+            .flat_map(|cy| (phase % 2..chunks_w).step_by(2).map(move |cx| (cx, cy)))
+            .filter(|&(cx, cy)| !tracked.active[cy * chunks_w + cx].is_empty());
+        if parallel {
+            let chunks: Vec<_> = chunks.collect();
+            woken.par_extend(chunks.into_par_iter().map(run));
+        } else {
+            woken.extend(chunks.map(run));
+        }
+    }
+    for rect in woken {
+        activity.wake(rect);
+    }
+}
@@ -0,0 +1,103 @@
+//! Burning: ignition, flames spreading into the air and burning out.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    chunks::Chunk,
+    elements::{Attributes, Cell, CellFlags, Combustion, ElementId, Elements},
+    world::{in_bounds, SimRng},
+};
+
+/// Burning cells are kept at least this hot, in °C, so they heat their
+/// surroundings through conduction and set nearby fuel alight.
+pub const FLAME_TEMPERATURE: f32 = 800.0;
+
+/// Chance per tick that a burning cell spawns its flame into a neighbouring
+/// air cell.
+pub const FLAME_CHANCE: f32 = 0.3;
+
+/// Ignites fuel that is, or touches something, at its ignition temperature,
+/// keeps burning cells hot, spawns flames and turns burnt out cells into
+/// what they leave behind.
+pub fn update_combustion(elems: &Elements, rng: &mut SimRng, ew: &mut Chunk, w: usize, h: usize) {
+    for i in ew.indices() {
+        let cell = ew[i];
+        let Some(combustion) = elems[cell.id].combustion else {
+            continue;
+        };
+
+        if !cell.burning() {
+            if touches_heat(ew, i, combustion.ignition_temperature, w, h) {
+                if rng.gen::<f32>() < combustion.flammability {
+                    ignite(&mut ew[i], &combustion, rng);
+                }
+                ew.wake(i);
+            }
+            continue;
+        }
+        ew.wake(i);
+
+        if cell.burn_time == 0 {
+            let mut ash = elems.create(combustion.leaves.unwrap_or(ElementId::AIR), rng);
+            ash.temperature = cell.temperature;
+            ew[i] = ash;
+            ew.wake(i);
+            continue;
+        }
+        ew[i].burn_time -= 1;
+        ew[i].temperature = cell.temperature.max(FLAME_TEMPERATURE);
+
+        if let Some(flame) = combustion.flame {
+            if rng.gen::<f32>() < FLAME_CHANCE {
+                spawn_flame(elems, rng, ew, i, flame, w, h);
+            }
+        }
+    }
+}
+
+/// Sets the cell burning for a randomized part of its burn duration, so
+/// flames don't all go out at once.
+fn ignite(cell: &mut Cell, combustion: &Combustion, rng: &mut SimRng) {
+    let duration = combustion.burn_duration;
+    cell.flags.insert(CellFlags::BURNING);
+    cell.burn_time = rng.gen_range(duration / 2..=duration);
+}
+
+/// Whether the cell at `index` or one of its 4-neighbours is at least `temperature`.
+fn touches_heat(ew: &Chunk, index: usize, temperature: f32, w: usize, h: usize) -> bool {
+    let (x, y) = ((index % w) as isize, (index / w) as isize);
+    [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)]
+        .iter()
+        .any(|&(dx, dy)| {
+            in_bounds(x + dx, y + dy, w, h)
+                && ew[(y + dy) as usize * w + (x + dx) as usize].temperature >= temperature
+        })
+}
+
+fn spawn_flame(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut Chunk,
+    index: usize,
+    flame: ElementId,
+    w: usize,
+    h: usize,
+) {
+    let (x, y) = ((index % w) as isize, (index / w) as isize);
+    let &(dx, dy) = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        .choose(rng)
+        .expect("directions are not empty");
+    if !in_bounds(x + dx, y + dy, w, h) {
+        return;
+    }
+
+    let j = (y + dy) as usize * w + (x + dx) as usize;
+    if elems[ew[j].id].has(Attributes::AIR) {
+        let mut cell = elems.create(flame, rng);
+        if let Some(combustion) = &elems[flame].combustion {
+            ignite(&mut cell, combustion, rng);
+        }
+        ew[j] = cell;
+        ew.wake(j);
+    }
+}
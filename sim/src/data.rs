@@ -0,0 +1,525 @@
+//! Loading element and reaction definitions from RON data files.
+
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    elements::{
+        Attributes, Combustion, Electrolysis, ElementColor, ElementDef, ElementId, Elements,
+        Moisture, PhaseChange,
+    },
+    heat::AMBIENT_TEMPERATURE,
+    moisture::MOISTURE_PER_CELL,
+    reactions::{ChemicalReaction, Reactions},
+};
+
+/// The element definitions shipped with the game.
+pub const DEFAULT_ELEMENTS: &str = include_str!("../assets/elements.ron");
+/// The reaction definitions shipped with the game.
+pub const DEFAULT_REACTIONS: &str = include_str!("../assets/reactions.ron");
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    NoElements,
+    Invalid {
+        element: String,
+        field: &'static str,
+        reason: String,
+    },
+    InvalidReaction {
+        index: usize,
+        field: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "could not read file: {err}"),
+            LoadError::Parse(err) => write!(f, "syntax error: {err}"),
+            LoadError::NoElements => write!(f, "no elements defined, at least Air is needed"),
+            LoadError::Invalid {
+                element,
+                field,
+                reason,
+            } => write!(f, "element `{element}`, field `{field}`: {reason}"),
+            LoadError::InvalidReaction {
+                index,
+                field,
+                reason,
+            } => write!(f, "reaction #{index}, field `{field}`: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+pub fn load_elements(path: impl AsRef<Path>) -> Result<Elements, LoadError> {
+    let src = fs::read_to_string(path).map_err(LoadError::Io)?;
+    parse_elements(&src)
+}
+
+/// Parses and validates a list of element definitions.
+pub fn parse_elements(src: &str) -> Result<Elements, LoadError> {
+    let entries: Vec<ElementEntry> = ron::from_str(src).map_err(LoadError::Parse)?;
+    if entries.is_empty() {
+        return Err(LoadError::NoElements);
+    }
+
+    let mut ids = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let invalid = |field, reason: &str| invalid(entry, i, field, reason);
+        if entry.name.is_empty() {
+            return Err(invalid("name", "must not be empty"));
+        }
+        let Ok(id) = u8::try_from(i) else {
+            return Err(invalid("name", "only 256 elements are supported"));
+        };
+        if ids.insert(entry.name.as_str(), ElementId(id)).is_some() {
+            return Err(invalid("name", "another element has the same name"));
+        }
+    }
+
+    let mut elements = Elements::default();
+    for (i, entry) in entries.iter().enumerate() {
+        elements.insert(entry.to_def(i, &ids)?);
+    }
+    Ok(elements)
+}
+
+pub fn load_reactions(path: impl AsRef<Path>, elements: &Elements) -> Result<Reactions, LoadError> {
+    let src = fs::read_to_string(path).map_err(LoadError::Io)?;
+    parse_reactions(&src, elements)
+}
+
+/// Parses a list of reactions, resolving element names against `elements`.
+pub fn parse_reactions(src: &str, elements: &Elements) -> Result<Reactions, LoadError> {
+    let entries: Vec<ReactionEntry> = ron::from_str(src).map_err(LoadError::Parse)?;
+
+    let mut reactions = Reactions::default();
+    for (index, entry) in entries.iter().enumerate() {
+        let invalid = |field, reason: String| LoadError::InvalidReaction {
+            index,
+            field,
+            reason,
+        };
+        let lookup = |field, name: &str| {
+            elements
+                .id(name)
+                .ok_or_else(|| invalid(field, format!("unknown element `{name}`")))
+        };
+
+        if !(0.0..=1.0).contains(&entry.probability) {
+            return Err(invalid("probability", "must be between 0 and 1".into()));
+        }
+        if let (Some(min), Some(max)) = (entry.min_temperature, entry.max_temperature) {
+            if min > max {
+                return Err(invalid(
+                    "min_temperature",
+                    "must not be above `max_temperature`".into(),
+                ));
+            }
+        }
+
+        reactions.insert(ChemicalReaction {
+            reactants: (
+                lookup("reactants", &entry.reactants.0)?,
+                lookup("reactants", &entry.reactants.1)?,
+            ),
+            products: (
+                lookup("products", &entry.products.0)?,
+                lookup("products", &entry.products.1)?,
+            ),
+            probability: entry.probability,
+            catalyst: entry
+                .catalyst
+                .as_deref()
+                .map(|name| lookup("catalyst", name))
+                .transpose()?,
+            min_temperature: entry.min_temperature,
+            max_temperature: entry.max_temperature,
+        });
+    }
+    Ok(reactions)
+}
+
+fn invalid(entry: &ElementEntry, index: usize, field: &'static str, reason: &str) -> LoadError {
+    LoadError::Invalid {
+        element: if entry.name.is_empty() {
+            format!("#{index}")
+        } else {
+            entry.name.clone()
+        },
+        field,
+        reason: reason.to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ElementEntry {
+    name: String,
+    #[serde(default)]
+    attributes: Vec<AttributeName>,
+    color: ColorEntry,
+    density: f32,
+    #[serde(default = "default_temperature")]
+    temperature: f32,
+    #[serde(default)]
+    conductivity: f32,
+    #[serde(default = "default_heat_capacity")]
+    heat_capacity: f32,
+    #[serde(default)]
+    melting_point: Option<PhaseEntry>,
+    #[serde(default)]
+    boiling_point: Option<PhaseEntry>,
+    #[serde(default)]
+    freezing_point: Option<PhaseEntry>,
+    #[serde(default)]
+    condensing_point: Option<PhaseEntry>,
+    #[serde(default)]
+    combustion: Option<CombustionEntry>,
+    #[serde(default)]
+    resistance: u8,
+    #[serde(default)]
+    glow: Option<ColorEntry>,
+    #[serde(default)]
+    electrolysis: Option<ElectrolysisEntry>,
+    #[serde(default)]
+    moisture: Option<MoistureEntry>,
+    #[serde(default)]
+    splash: f32,
+    #[serde(default)]
+    viscosity: f32,
+    #[serde(default)]
+    pillar_like: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PhaseEntry {
+    temperature: f32,
+    into: String,
+    #[serde(default)]
+    latent_heat: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CombustionEntry {
+    flammability: f32,
+    ignition_temperature: f32,
+    burn_duration: u16,
+    #[serde(default)]
+    leaves: Option<String>,
+    #[serde(default)]
+    flame: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ElectrolysisEntry {
+    chance: f32,
+    into: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MoistureEntry {
+    absorbs: String,
+    capacity: u8,
+    drying: f32,
+    evaporates_to: String,
+    #[serde(default)]
+    saturates_to: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReactionEntry {
+    reactants: (String, String),
+    products: (String, String),
+    #[serde(default = "default_probability")]
+    probability: f32,
+    #[serde(default)]
+    catalyst: Option<String>,
+    #[serde(default)]
+    min_temperature: Option<f32>,
+    #[serde(default)]
+    max_temperature: Option<f32>,
+}
+
+fn default_probability() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorEntry {
+    r: f32,
+    g: f32,
+    b: f32,
+    #[serde(default)]
+    rv: f32,
+    #[serde(default)]
+    gv: f32,
+    #[serde(default)]
+    bv: f32,
+    #[serde(default)]
+    dv: f32,
+    #[serde(default = "default_max_dist")]
+    max_dist: f32,
+}
+
+fn default_temperature() -> f32 {
+    AMBIENT_TEMPERATURE
+}
+
+fn default_heat_capacity() -> f32 {
+    1.0
+}
+
+fn default_max_dist() -> f32 {
+    ElementColor::default().max_dist
+}
+
+#[derive(Clone, Copy, Deserialize)]
+enum AttributeName {
+    CanFall,
+    Solid,
+    Liquid,
+    Gas,
+    Immovable,
+    Sparkle,
+    Air,
+    Conductive,
+    PowerSource,
+}
+
+impl From<AttributeName> for Attributes {
+    fn from(name: AttributeName) -> Self {
+        match name {
+            AttributeName::CanFall => Attributes::CAN_FALL,
+            AttributeName::Solid => Attributes::SOLID,
+            AttributeName::Liquid => Attributes::LIQUID,
+            AttributeName::Gas => Attributes::GAS,
+            AttributeName::Immovable => Attributes::IMMOVABLE,
+            AttributeName::Sparkle => Attributes::SPARKLE,
+            AttributeName::Air => Attributes::AIR,
+            AttributeName::Conductive => Attributes::CONDUCTIVE,
+            AttributeName::PowerSource => Attributes::POWER_SOURCE,
+        }
+    }
+}
+
+impl ElementEntry {
+    fn to_def(
+        &self,
+        index: usize,
+        ids: &HashMap<&str, ElementId>,
+    ) -> Result<ElementDef, LoadError> {
+        let invalid = |field, reason: &str| invalid(self, index, field, reason);
+        let lookup = |field, name: &str| {
+            ids.get(name)
+                .copied()
+                .ok_or_else(|| invalid(field, &format!("unknown element `{name}`")))
+        };
+        let phase = |field, entry: &Option<PhaseEntry>| {
+            let Some(entry) = entry else {
+                return Ok(None);
+            };
+            if !entry.temperature.is_finite() {
+                return Err(invalid(field, "`temperature` must be a number"));
+            }
+            if !(entry.latent_heat >= 0.0 && entry.latent_heat.is_finite()) {
+                return Err(invalid(field, "`latent_heat` must not be negative"));
+            }
+            Ok(Some(PhaseChange {
+                temperature: entry.temperature,
+                into: lookup(field, &entry.into)?,
+                latent_heat: entry.latent_heat,
+            }))
+        };
+
+        let attributes = self
+            .attributes
+            .iter()
+            .fold(Attributes::empty(), |acc, &name| acc | name.into());
+        if index == 0 && !attributes.contains(Attributes::AIR) {
+            return Err(invalid(
+                "attributes",
+                "the first element must have the `Air` attribute",
+            ));
+        }
+
+        let color = self
+            .color
+            .to_color()
+            .map_err(|reason| invalid("color", &reason))?;
+        let glow = self
+            .glow
+            .as_ref()
+            .map(|glow| glow.to_color().map_err(|reason| invalid("glow", &reason)))
+            .transpose()?;
+        if !(self.density > 0.0 && self.density.is_finite()) {
+            return Err(invalid("density", "must be positive"));
+        }
+        if !self.temperature.is_finite() {
+            return Err(invalid("temperature", "must be a number"));
+        }
+        if !(0.0..=1.0).contains(&self.conductivity) {
+            return Err(invalid("conductivity", "must be between 0 and 1"));
+        }
+        // the explicit heat update is only stable for capacities of at least 1
+        if !(self.heat_capacity >= 1.0 && self.heat_capacity.is_finite()) {
+            return Err(invalid("heat_capacity", "must be at least 1"));
+        }
+        if !(0.0..=1.0).contains(&self.splash) {
+            return Err(invalid("splash", "must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.viscosity) {
+            return Err(invalid("viscosity", "must be between 0 and 1"));
+        }
+        if let Some(chance) = self.pillar_like {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(invalid("pillar_like", "must be between 0 and 1"));
+            }
+        }
+
+        let combustion = match &self.combustion {
+            Some(entry) => {
+                if !(0.0..=1.0).contains(&entry.flammability) {
+                    return Err(invalid(
+                        "combustion",
+                        "`flammability` must be between 0 and 1",
+                    ));
+                }
+                if !entry.ignition_temperature.is_finite() {
+                    return Err(invalid(
+                        "combustion",
+                        "`ignition_temperature` must be a number",
+                    ));
+                }
+                Some(Combustion {
+                    flammability: entry.flammability,
+                    ignition_temperature: entry.ignition_temperature,
+                    burn_duration: entry.burn_duration,
+                    leaves: entry
+                        .leaves
+                        .as_deref()
+                        .map(|name| lookup("combustion", name))
+                        .transpose()?,
+                    flame: entry
+                        .flame
+                        .as_deref()
+                        .map(|name| lookup("combustion", name))
+                        .transpose()?,
+                })
+            }
+            None => None,
+        };
+
+        let electrolysis = match &self.electrolysis {
+            Some(entry) => {
+                if !(0.0..=1.0).contains(&entry.chance) {
+                    return Err(invalid("electrolysis", "`chance` must be between 0 and 1"));
+                }
+                if entry.into.is_empty() {
+                    return Err(invalid("electrolysis", "`into` must not be empty"));
+                }
+                Some(Electrolysis {
+                    chance: entry.chance,
+                    into: entry
+                        .into
+                        .iter()
+                        .map(|name| lookup("electrolysis", name))
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+            None => None,
+        };
+
+        let moisture = match &self.moisture {
+            Some(entry) => {
+                if entry.capacity < MOISTURE_PER_CELL {
+                    return Err(invalid(
+                        "moisture",
+                        &format!("`capacity` must be at least {MOISTURE_PER_CELL}"),
+                    ));
+                }
+                if !(0.0..=1.0).contains(&entry.drying) {
+                    return Err(invalid("moisture", "`drying` must be between 0 and 1"));
+                }
+                Some(Moisture {
+                    absorbs: lookup("moisture", &entry.absorbs)?,
+                    capacity: entry.capacity,
+                    drying: entry.drying,
+                    evaporates_to: lookup("moisture", &entry.evaporates_to)?,
+                    saturates_to: entry
+                        .saturates_to
+                        .as_deref()
+                        .map(|name| lookup("moisture", name))
+                        .transpose()?,
+                })
+            }
+            None => None,
+        };
+
+        Ok(ElementDef {
+            temperature: self.temperature,
+            conductivity: self.conductivity,
+            heat_capacity: self.heat_capacity,
+            melting_point: phase("melting_point", &self.melting_point)?,
+            boiling_point: phase("boiling_point", &self.boiling_point)?,
+            freezing_point: phase("freezing_point", &self.freezing_point)?,
+            condensing_point: phase("condensing_point", &self.condensing_point)?,
+            combustion,
+            resistance: self.resistance,
+            glow,
+            electrolysis,
+            moisture,
+            splash: self.splash,
+            viscosity: self.viscosity,
+            pillar_like: self.pillar_like,
+            ..ElementDef::new(&self.name, attributes, color, self.density)
+        })
+    }
+}
+
+impl ColorEntry {
+    fn to_color(&self) -> Result<ElementColor, String> {
+        for (field, value) in [("r", self.r), ("g", self.g), ("b", self.b)] {
+            if !(0.0..=255.0).contains(&value) {
+                return Err(format!("`{field}` must be between 0 and 255"));
+            }
+        }
+        for (field, value) in [
+            ("rv", self.rv),
+            ("gv", self.gv),
+            ("bv", self.bv),
+            ("dv", self.dv),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("`{field}` must be between 0 and 1"));
+            }
+        }
+        if !(self.max_dist > 0.0 && self.max_dist.is_finite()) {
+            return Err("`max_dist` must be positive".into());
+        }
+
+        Ok(ElementColor {
+            r: self.r / 255.0,
+            g: self.g / 255.0,
+            b: self.b / 255.0,
+            rv: self.rv,
+            gv: self.gv,
+            bv: self.bv,
+            dv: self.dv,
+            max_dist: self.max_dist,
+        })
+    }
+}
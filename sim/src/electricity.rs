@@ -0,0 +1,86 @@
+//! Charge flowing from power sources through conductive cells.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    chunks::Chunk,
+    elements::{Attributes, Elements},
+    world::{in_bounds, SimRng},
+};
+
+/// Charge held by power sources. Every conductive cell crossed costs at
+/// least 1, so this is also the longest wire a source can drive.
+pub const MAX_CHARGE: u8 = u8::MAX;
+
+/// Heat released per tick by a cell at full charge, per point of resistance.
+pub const RESISTIVE_HEATING: f32 = 0.25;
+
+/// Moves charge one cell along conductors, heats resistive cells and splits
+/// charged cells that undergo electrolysis.
+///
+/// Each conductive cell takes the highest charge among the neighbours that
+/// had more charge than itself at the start of the pass, minus 1 plus its
+/// resistance. So charge spreads one cell per tick, and once the source is
+/// gone the wire empties from that end at the same speed instead of cells
+/// feeding each other. `charges` holds every cell's charge from the start
+/// of the pass.
+pub fn update_electricity(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut Chunk,
+    charges: &[u8],
+    w: usize,
+    h: usize,
+) {
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    for y in ew.ys() {
+        for x in ew.xs() {
+            let i = y * w + x;
+            let def = &elems[ew[i].id];
+
+            let charge = if def.has(Attributes::POWER_SOURCE) {
+                MAX_CHARGE
+            } else if def.has(Attributes::CONDUCTIVE) {
+                let mut highest = 0;
+                for &(dx, dy) in &directions {
+                    let nx = x as isize + dx;
+                    let ny = y as isize + dy;
+                    if in_bounds(nx, ny, w, h) {
+                        let other = charges[ny as usize * w + nx as usize];
+                        if other > charges[i] {
+                            highest = highest.max(other);
+                        }
+                    }
+                }
+                highest.saturating_sub(def.resistance.saturating_add(1))
+            } else {
+                0
+            };
+            ew[i].charge = charge;
+            if charge == 0 {
+                if charges[i] > 0 {
+                    ew.wake(i);
+                }
+                continue;
+            }
+            ew.wake(i);
+
+            let load = charge as f32 / MAX_CHARGE as f32;
+            ew[i].temperature +=
+                def.resistance as f32 * load * RESISTIVE_HEATING / def.heat_capacity;
+
+            if let Some(electrolysis) = &def.electrolysis {
+                if rng.gen::<f32>() < electrolysis.chance * load {
+                    let &into = electrolysis
+                        .into
+                        .choose(rng)
+                        .expect("electrolysis products are not empty");
+                    let mut cell = elems.create(into, rng);
+                    cell.temperature = ew[i].temperature;
+                    ew[i] = cell;
+                    ew.wake(i);
+                }
+            }
+        }
+    }
+}
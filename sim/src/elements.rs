@@ -0,0 +1,381 @@
+use std::{collections::HashMap, ops::Index};
+
+use bitflags::bitflags;
+use lazy_static::lazy_static;
+use rand::Rng;
+
+use crate::{
+    data::{parse_elements, DEFAULT_ELEMENTS},
+    heat::AMBIENT_TEMPERATURE,
+    moisture::WET_DARKENING,
+    motion::{MAX_SPEED, VELOCITY_SCALE},
+};
+
+/// Index of an element definition in an [`Elements`] registry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub struct ElementId(pub u8);
+
+impl ElementId {
+    /// The registry always starts with Air, which fills empty space.
+    pub const AIR: ElementId = ElementId(0);
+}
+
+lazy_static! {
+    pub static ref DEF_ELEMS: Elements =
+        parse_elements(DEFAULT_ELEMENTS).expect("built-in element definitions are invalid");
+}
+
+bitflags! {
+    /// Boolean properties of an element, checked in the hot simulation loops.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct Attributes: u16 {
+        const CAN_FALL = 1 << 0;
+        const SOLID = 1 << 1;
+        const LIQUID = 1 << 2;
+        const GAS = 1 << 3;
+        const IMMOVABLE = 1 << 4;
+        const SPARKLE = 1 << 5;
+        const AIR = 1 << 6;
+        const CONDUCTIVE = 1 << 7;
+        /// Holds full charge and feeds it into touching conductors.
+        const POWER_SOURCE = 1 << 8;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ElementColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub rv: f32,
+    pub gv: f32,
+    pub bv: f32,
+    pub dv: f32,
+    pub max_dist: f32,
+}
+
+impl ElementColor {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(r: f32, g: f32, b: f32, rv: f32, gv: f32, bv: f32, dv: f32, max_dist: f32) -> Self {
+        ElementColor {
+            r,
+            g,
+            b,
+            rv,
+            gv,
+            bv,
+            dv,
+            max_dist,
+        }
+    }
+}
+
+impl Default for ElementColor {
+    fn default() -> Self {
+        ElementColor {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            rv: 0.0,
+            gv: 0.0,
+            bv: 0.0,
+            dv: 0.0,
+            max_dist: 5.0,
+        }
+    }
+}
+
+/// Registry of element definitions, indexed by [`ElementId`].
+#[derive(Clone, Debug, Default)]
+pub struct Elements {
+    defs: Vec<ElementDef>,
+    by_name: HashMap<Box<str>, ElementId>,
+}
+
+impl Elements {
+    pub fn insert(&mut self, def: ElementDef) -> ElementId {
+        let id = ElementId(
+            self.defs
+                .len()
+                .try_into()
+                .expect("too many elements for a u8 id"),
+        );
+        self.by_name.insert(def.name.clone(), id);
+        self.defs.push(def);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<ElementId> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ElementId, &ElementDef)> {
+        self.defs
+            .iter()
+            .enumerate()
+            .map(|(i, def)| (ElementId(i as u8), def))
+    }
+
+    /// Creates a fresh cell of the given element with a randomly varied color.
+    pub fn create(&self, id: ElementId, rng: &mut impl Rng) -> Cell {
+        let mut cell = Cell {
+            temperature: self[id].temperature,
+            burn_time: 0,
+            id,
+            flags: CellFlags::FALLING,
+            charge: 0,
+            moisture: 0,
+            velocity: [0; 2],
+            color: [0; 3],
+        };
+        cell.vary_color(&self[id].color, rng);
+        cell
+    }
+}
+
+impl Index<ElementId> for Elements {
+    type Output = ElementDef;
+
+    fn index(&self, id: ElementId) -> &ElementDef {
+        &self.defs[id.0 as usize]
+    }
+}
+
+/// Static properties shared by every cell of one element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementDef {
+    pub name: Box<str>,
+    pub attributes: Attributes,
+    pub color: ElementColor,
+    /// Starting temperature of new cells, in °C.
+    pub temperature: f32,
+    pub density: f32,
+    /// How readily heat flows through the element, from 0 to 1.
+    pub conductivity: f32,
+    /// Heat needed to warm a cell by one degree, at least 1.
+    pub heat_capacity: f32,
+    /// Transition taken when warmed to or above its temperature.
+    pub melting_point: Option<PhaseChange>,
+    /// Like `melting_point`, checked after it.
+    pub boiling_point: Option<PhaseChange>,
+    /// Transition taken when cooled to or below its temperature.
+    pub freezing_point: Option<PhaseChange>,
+    /// Like `freezing_point`, checked after it.
+    pub condensing_point: Option<PhaseChange>,
+    pub combustion: Option<Combustion>,
+    /// Charge lost on top of the usual 1 when current crosses the cell,
+    /// turned into heat.
+    pub resistance: u8,
+    /// Color used instead of `color` while the cell carries charge.
+    pub glow: Option<ElementColor>,
+    pub electrolysis: Option<Electrolysis>,
+    pub moisture: Option<Moisture>,
+    /// Share of the falling speed turned into sideways speed on impact.
+    pub splash: f32,
+    /// How thick a liquid is, from 0 to 1. Thicker liquids spread sideways
+    /// less often and less far, and lose their sideways speed sooner.
+    pub viscosity: f32,
+    /// Cells hold their place while connected to the floor or to an
+    /// immovable cell through other pillar-like cells. Once cut off, each
+    /// has this chance per tick to break loose and fall.
+    pub pillar_like: Option<f32>,
+}
+
+impl ElementDef {
+    pub fn new(name: &str, attributes: Attributes, color: ElementColor, density: f32) -> Self {
+        ElementDef {
+            name: name.into(),
+            attributes,
+            color,
+            temperature: AMBIENT_TEMPERATURE,
+            density,
+            conductivity: 0.0,
+            heat_capacity: 1.0,
+            melting_point: None,
+            boiling_point: None,
+            freezing_point: None,
+            condensing_point: None,
+            combustion: None,
+            resistance: 0,
+            glow: None,
+            electrolysis: None,
+            moisture: None,
+            splash: 0.0,
+            viscosity: 0.0,
+            pillar_like: None,
+        }
+    }
+
+    pub fn has(&self, attributes: Attributes) -> bool {
+        self.attributes.contains(attributes)
+    }
+}
+
+/// A temperature-driven change of state, e.g. Water boiling into Steam.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseChange {
+    /// Threshold in °C.
+    pub temperature: f32,
+    pub into: ElementId,
+    /// Heat absorbed when warming past the threshold, or released when cooling past it.
+    pub latent_heat: f32,
+}
+
+/// How an element burns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Combustion {
+    /// Chance per tick to catch fire while it or a neighbour is at or above
+    /// `ignition_temperature`.
+    pub flammability: f32,
+    pub ignition_temperature: f32,
+    /// Ticks a burning cell lasts before it burns out.
+    pub burn_duration: u16,
+    /// What is left behind once burnt out; Air if `None`.
+    pub leaves: Option<ElementId>,
+    /// Element spawned into neighbouring air while burning.
+    pub flame: Option<ElementId>,
+}
+
+/// How an element is split up by current.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Electrolysis {
+    /// Chance per tick that a charged cell splits.
+    pub chance: f32,
+    /// The cell turns into one of these, picked at random, so listing an
+    /// element twice makes it twice as common.
+    pub into: Vec<ElementId>,
+}
+
+/// How an element soaks up liquid. Each absorbed liquid cell adds
+/// [`MOISTURE_PER_CELL`](crate::moisture::MOISTURE_PER_CELL) to the cell's moisture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Moisture {
+    pub absorbs: ElementId,
+    /// Most moisture a cell can hold, at least one liquid cell's worth.
+    pub capacity: u8,
+    /// Chance per tick at 100 °C to give off one liquid cell's worth into
+    /// neighbouring air, falling off linearly down to none at 0 °C.
+    pub drying: f32,
+    pub evaporates_to: ElementId,
+    /// A saturated cell that is offered more liquid turns into this.
+    pub saturates_to: Option<ElementId>,
+}
+
+/// Per-cell state. Everything shared by an element lives in its [`ElementDef`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub temperature: f32,
+    /// Ticks left until a burning cell burns out.
+    pub burn_time: u16,
+    pub id: ElementId,
+    pub flags: CellFlags,
+    /// Electric charge: full at a power source, dropping along conductors.
+    pub charge: u8,
+    /// Absorbed liquid, see [`Moisture`].
+    pub moisture: u8,
+    /// In steps of 1/[`VELOCITY_SCALE`] cells per tick.
+    velocity: [i8; 2],
+    /// Offset from the element's base color, in 1/256 steps.
+    color: [i8; 3],
+}
+
+bitflags! {
+    /// Per-cell state bits.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct CellFlags: u8 {
+        const FALLING = 1 << 0;
+        const BURNING = 1 << 1;
+        /// A pillar-like cell holding its place; cleared when it breaks loose.
+        const HELD = 1 << 2;
+        /// Already moved during the current movement pass.
+        const MOVED = 1 << 3;
+    }
+}
+
+impl Cell {
+    pub fn falling(&self) -> bool {
+        self.flags.contains(CellFlags::FALLING)
+    }
+
+    pub fn burning(&self) -> bool {
+        self.flags.contains(CellFlags::BURNING)
+    }
+
+    pub fn held(&self) -> bool {
+        self.flags.contains(CellFlags::HELD)
+    }
+
+    pub fn moved(&self) -> bool {
+        self.flags.contains(CellFlags::MOVED)
+    }
+
+    /// Velocity in cells per tick, `[x, y]` with y pointing up.
+    pub fn velocity(&self) -> [f32; 2] {
+        self.velocity.map(|v| v as f32 / VELOCITY_SCALE)
+    }
+
+    /// Sets the velocity, clamped to [`MAX_SPEED`] on each axis. Rounds
+    /// towards zero, so drag brings cells to a full stop.
+    pub fn set_velocity(&mut self, velocity: [f32; 2]) {
+        self.velocity = velocity.map(|v| (v.clamp(-MAX_SPEED, MAX_SPEED) * VELOCITY_SCALE) as i8);
+    }
+
+    pub fn render(&self, color: &ElementColor) -> u32 {
+        let [r, g, b, _] = self.render_rgba(color);
+        u32::from_be_bytes([0, r, g, b])
+    }
+
+    /// The cell's color as opaque RGBA bytes.
+    pub fn render_rgba(&self, color: &ElementColor) -> [u8; 4] {
+        let [r, g, b] = self.render_color(color);
+        // wet cells look darker
+        let shade = 1.0 - WET_DARKENING * self.moisture as f32 / u8::MAX as f32;
+        let [r, g, b] = [r, g, b].map(|c| (c * shade * 255.0) as u8);
+        [r, g, b, 255]
+    }
+
+    fn render_color(&self, color: &ElementColor) -> [f32; 3] {
+        let base = [color.r, color.g, color.b];
+        std::array::from_fn(|i| (base[i] + self.color[i] as f32 / 256.0).clamp(0.0, 1.0))
+    }
+
+    pub fn vary_color(&mut self, color: &ElementColor, rng: &mut impl Rng) {
+        let [r, g, b] = self.render_color(color);
+
+        let darken_delta = rng.gen_range(-color.dv..=color.dv);
+        let mut adjust_color = |color: f32, variance: f32| -> f32 {
+            let delta = rng.gen_range(-variance..=variance);
+            let new_color = color + delta + darken_delta;
+            new_color.clamp(0.0, 1.0)
+        };
+
+        let new_r = adjust_color(r, color.rv);
+        let new_g = adjust_color(g, color.gv);
+        let new_b = adjust_color(b, color.bv);
+
+        let distance =
+            ((new_r - color.r).powi(2) + (new_g - color.g).powi(2) + (new_b - color.b).powi(2))
+                .sqrt();
+
+        let scale = if distance > color.max_dist {
+            color.max_dist / distance
+        } else {
+            1.0
+        };
+        let offset =
+            |new: f32, base: f32| ((new - base) * scale * 256.0).round().clamp(-128.0, 127.0) as i8;
+        self.color = [
+            offset(new_r, color.r),
+            offset(new_g, color.g),
+            offset(new_b, color.b),
+        ];
+    }
+}
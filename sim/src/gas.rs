@@ -0,0 +1,223 @@
+//! Gas pressure, diffusion and a coarse wind field that pushes gases around.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    chunks::{Chunk, ChunkActivity},
+    elements::{Attributes, Cell, CellFlags, Elements},
+    world::{immovable, in_bounds, SimRng},
+};
+
+/// Side length, in cells, of the square blocks the wind field is made of.
+pub const WIND_BLOCK: usize = 8;
+
+/// Fastest wind, in cells per tick.
+pub const MAX_WIND: f32 = 2.0;
+
+/// Wind gained per tick between two blocks whose gas concentration differs
+/// by 1, blowing from the fuller towards the emptier block.
+pub const PRESSURE_FORCE: f32 = 0.2;
+
+/// Share of its speed the wind keeps every tick.
+pub const WIND_DAMPING: f32 = 0.97;
+
+/// Wind slower than this, in cells per tick, stops, so the field calms down
+/// completely once there's no gas left to drive it.
+pub const CALM_WIND: f32 = 1e-3;
+
+/// Share of each block's wind that evens out with its neighbours every tick.
+pub const WIND_SPREAD: f32 = 0.2;
+
+/// Chance per tick that a gas cell swaps with a random neighbouring cell of
+/// air or another gas, so gases mix and spread into empty space.
+pub const DIFFUSION: f32 = 0.3;
+
+/// Wind velocity per block of [`WIND_BLOCK`]² cells, in cells per tick.
+#[derive(Clone, Debug)]
+pub struct WindField {
+    width: usize,
+    height: usize,
+    velocity: Vec<[f32; 2]>,
+    /// Share of each block taken up by gas, refreshed every tick.
+    concentration: Vec<f32>,
+    scratch: Vec<[f32; 2]>,
+}
+
+impl WindField {
+    /// Creates a still wind field covering a world of the given size.
+    pub fn new(world_width: usize, world_height: usize) -> Self {
+        let width = world_width.div_ceil(WIND_BLOCK);
+        let height = world_height.div_ceil(WIND_BLOCK);
+        WindField {
+            width,
+            height,
+            velocity: vec![[0.0; 2]; width * height],
+            concentration: vec![0.0; width * height],
+            scratch: Vec::new(),
+        }
+    }
+
+    /// The wind at cell `(x, y)`.
+    pub fn at(&self, x: usize, y: usize) -> [f32; 2] {
+        self.velocity[(y / WIND_BLOCK) * self.width + x / WIND_BLOCK]
+    }
+
+    /// Share of the block around cell `(x, y)` taken up by gas, from 0 to 1.
+    pub fn concentration_at(&self, x: usize, y: usize) -> f32 {
+        self.concentration[(y / WIND_BLOCK) * self.width + x / WIND_BLOCK]
+    }
+
+    /// Adds `velocity` to the wind of every block within `radius` cells of
+    /// cell `(x, y)`, like a blower pointed that way.
+    pub fn blow(&mut self, x: f32, y: f32, radius: f32, velocity: [f32; 2]) {
+        let block = WIND_BLOCK as f32;
+        for by in 0..self.height {
+            for bx in 0..self.width {
+                let cx = (bx as f32 + 0.5) * block;
+                let cy = (by as f32 + 0.5) * block;
+                if (cx - x).hypot(cy - y) > radius + block / 2.0 {
+                    continue;
+                }
+                let v = &mut self.velocity[by * self.width + bx];
+                for axis in 0..2 {
+                    v[axis] = (v[axis] + velocity[axis]).clamp(-MAX_WIND, MAX_WIND);
+                }
+            }
+        }
+    }
+
+    /// Measures gas concentration, accelerates the wind down its gradient,
+    /// then damps and smooths it. Runs before [`update_gas_flow`].
+    ///
+    /// Gas never settles, so only awake cells are counted, and without any
+    /// gas or wind nothing is done at all.
+    pub(crate) fn update(
+        &mut self,
+        elems: &Elements,
+        ew: &[Cell],
+        activity: &ChunkActivity,
+        w: usize,
+    ) {
+        self.concentration.fill(0.0);
+        let mut gas = false;
+        for rect in activity.awake() {
+            for y in rect.y0..rect.y1 {
+                for x in rect.x0..rect.x1 {
+                    if elems[ew[y * w + x].id].has(Attributes::GAS) {
+                        self.concentration[(y / WIND_BLOCK) * self.width + x / WIND_BLOCK] += 1.0;
+                        gas = true;
+                    }
+                }
+            }
+        }
+        if !gas && self.velocity.iter().all(|&v| v == [0.0; 2]) {
+            return;
+        }
+        for c in &mut self.concentration {
+            *c /= (WIND_BLOCK * WIND_BLOCK) as f32;
+        }
+
+        let (bw, bh) = (self.width, self.height);
+        let concentration = |bx: isize, by: isize, own: f32| {
+            if in_bounds(bx, by, bw, bh) {
+                self.concentration[by as usize * bw + bx as usize]
+            } else {
+                own
+            }
+        };
+        self.scratch.clear();
+        for by in 0..bh {
+            for bx in 0..bw {
+                let i = by * bw + bx;
+                let own = self.concentration[i];
+                let (x, y) = (bx as isize, by as isize);
+                let gradient = [
+                    concentration(x + 1, y, own) - concentration(x - 1, y, own),
+                    concentration(x, y + 1, own) - concentration(x, y - 1, own),
+                ];
+
+                let mut average = [0.0; 2];
+                let mut count = 0.0;
+                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    if in_bounds(x + dx, y + dy, bw, bh) {
+                        let v = self.velocity[(y + dy) as usize * bw + (x + dx) as usize];
+                        average[0] += v[0];
+                        average[1] += v[1];
+                        count += 1.0;
+                    }
+                }
+
+                let v = self.velocity[i];
+                self.scratch.push(std::array::from_fn(|axis| {
+                    let spread = if count > 0.0 {
+                        v[axis] + (average[axis] / count - v[axis]) * WIND_SPREAD
+                    } else {
+                        v[axis]
+                    };
+                    let pushed = spread - gradient[axis] / 2.0 * PRESSURE_FORCE;
+                    let wind = (pushed * WIND_DAMPING).clamp(-MAX_WIND, MAX_WIND);
+                    if wind.abs() < CALM_WIND {
+                        0.0
+                    } else {
+                        wind
+                    }
+                }));
+            }
+        }
+        std::mem::swap(&mut self.velocity, &mut self.scratch);
+    }
+}
+
+/// Lets gas cells drift with the wind and diffuse into air and other gases.
+/// The wind should be updated from the gas concentration first, so crowded
+/// gas pushes outwards.
+pub fn update_gas_flow(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut Chunk,
+    wind: &WindField,
+    w: usize,
+    h: usize,
+) {
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    for y in ew.ys() {
+        for x in ew.xs() {
+            let i = y * w + x;
+            let cell = ew[i];
+            if cell.moved() || !elems[cell.id].has(Attributes::GAS) {
+                continue;
+            }
+            // gases never quite settle
+            ew.wake(i);
+
+            let [wx, wy] = wind.at(x, y);
+            let blown = [
+                (wx, (wx.signum() as isize, 0)),
+                (wy, (0, wy.signum() as isize)),
+            ]
+            .into_iter()
+            .find(|&(speed, _)| rng.gen::<f32>() < speed.abs())
+            .map(|(_, step)| step);
+            let step = match blown {
+                Some(step) => Some(step),
+                None if rng.gen::<f32>() < DIFFUSION => directions.choose(rng).copied(),
+                None => None,
+            };
+
+            let Some((dx, dy)) = step else {
+                continue;
+            };
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            if !in_bounds(nx, ny, w, h) {
+                continue;
+            }
+            let j = ny as usize * w + nx as usize;
+            let other = &elems[ew[j].id];
+            let open = other.has(Attributes::AIR) || other.has(Attributes::GAS);
+            if open && ew[j].id != cell.id && !immovable(elems, &ew[j]) {
+                ew.swap(i, j);
+                ew[j].flags.insert(CellFlags::MOVED);
+            }
+        }
+    }
+}
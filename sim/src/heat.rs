@@ -0,0 +1,129 @@
+//! Heat conduction between neighbouring cells and the phase changes it drives.
+
+use crate::{
+    chunks::Chunk,
+    elements::{Cell, Elements, PhaseChange},
+    world::{in_bounds, SimRng},
+};
+
+/// Room temperature in °C, the starting temperature of most elements.
+pub const AMBIENT_TEMPERATURE: f32 = 20.0;
+
+/// Fraction of a temperature difference that flows between two cells of
+/// conductivity 1 in one tick. With four neighbours and heat capacities of
+/// at least 1 this keeps the explicit update stable.
+pub const CONDUCTION_RATE: f32 = 0.25;
+
+/// Cells whose temperature changes by less than this many °C in a tick
+/// don't keep their chunk awake.
+pub const SETTLED_HEAT_FLOW: f32 = 0.01;
+
+/// What lies beyond the edge of the world, thermally.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    /// No heat crosses the edge.
+    Insulated,
+    /// The edge is held at a fixed temperature, like an infinite reservoir.
+    Fixed(f32),
+}
+
+/// Conducts heat between 4-neighbours. Every cell is updated from the
+/// temperatures at the start of the pass, so the result doesn't depend on
+/// iteration order, and total heat is conserved except through the edge.
+/// Sleeping cells don't update themselves, so a cell next to one also
+/// applies the other end of their flow to it.
+/// `temps` holds every cell's temperature from the start of the pass.
+pub fn update_heat(
+    elems: &Elements,
+    ew: &mut Chunk,
+    temps: &[f32],
+    boundary: Boundary,
+    w: usize,
+    h: usize,
+) {
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    for y in ew.ys() {
+        for x in ew.xs() {
+            let i = y * w + x;
+            let def = &elems[ew[i].id];
+            if def.conductivity == 0.0 {
+                continue;
+            }
+
+            let mut flow = 0.0;
+            for &(dx, dy) in &directions {
+                let nx = x as isize + dx;
+                let ny = y as isize + dy;
+                if in_bounds(nx, ny, w, h) {
+                    let j = ny as usize * w + nx as usize;
+                    let other = &elems[ew[j].id];
+                    let edge =
+                        conductance(def.conductivity, other.conductivity) * (temps[j] - temps[i]);
+                    flow += edge;
+                    if edge != 0.0 && !ew.is_active(nx as usize, ny as usize) {
+                        let change = -edge / other.heat_capacity;
+                        ew[j].temperature += change;
+                        if change.abs() >= SETTLED_HEAT_FLOW {
+                            ew.wake(j);
+                        }
+                    }
+                } else if let Boundary::Fixed(t) = boundary {
+                    flow += def.conductivity * CONDUCTION_RATE * (t - temps[i]);
+                }
+            }
+            let change = flow / def.heat_capacity;
+            ew[i].temperature = temps[i] + change;
+            if change.abs() >= SETTLED_HEAT_FLOW {
+                ew.wake(i);
+            }
+        }
+    }
+}
+
+/// Turns cells that have crossed a melting, boiling, freezing or condensing
+/// point into their new element. A cell only transitions once it holds
+/// enough heat beyond the threshold to pay the latent heat, so e.g. fresh
+/// Steam doesn't condense again the moment it forms.
+pub fn update_phases(elems: &Elements, rng: &mut SimRng, ew: &mut Chunk) {
+    for i in ew.indices() {
+        let cell = &mut ew[i];
+        let def = &elems[cell.id];
+        let t = cell.temperature;
+
+        let rising = [def.melting_point, def.boiling_point]
+            .into_iter()
+            .flatten()
+            .find(|phase| def.heat_capacity * (t - phase.temperature) >= phase.latent_heat);
+        let falling = [def.freezing_point, def.condensing_point]
+            .into_iter()
+            .flatten()
+            .find(|phase| def.heat_capacity * (phase.temperature - t) >= phase.latent_heat);
+
+        let (phase, heat) = match (rising, falling) {
+            (Some(phase), _) => (phase, -phase.latent_heat),
+            (None, Some(phase)) => (phase, phase.latent_heat),
+            (None, None) => continue,
+        };
+        let excess = def.heat_capacity * (t - phase.temperature) + heat;
+        *cell = transition(elems, rng, excess, phase);
+        ew.wake(i);
+    }
+}
+
+/// Creates the new cell. `excess` is the heat it holds relative to the
+/// threshold once the latent heat has been paid.
+fn transition(elems: &Elements, rng: &mut SimRng, excess: f32, phase: PhaseChange) -> Cell {
+    let mut cell = elems.create(phase.into, rng);
+    cell.temperature = phase.temperature + excess / elems[phase.into].heat_capacity;
+    cell
+}
+
+/// Conductance between two cells: the harmonic mean of their conductivities,
+/// so a single insulating cell blocks the flow like it would in reality.
+fn conductance(a: f32, b: f32) -> f32 {
+    if a + b == 0.0 {
+        0.0
+    } else {
+        CONDUCTION_RATE * 2.0 * a * b / (a + b)
+    }
+}
@@ -0,0 +1,341 @@
+//! Drawing the world into RGBA images on the CPU and saving them as PNG, for
+//! screenshots and golden-image checks on machines without a GPU.
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use bitflags::bitflags;
+
+use crate::{
+    chunks::{Rect, CHUNK_SIZE},
+    view::{Legend, View},
+    world::World,
+};
+
+bitflags! {
+    /// Debug drawings laid over the cells.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct Overlays: u8 {
+        /// Outlines the cells each chunk updated last tick.
+        const ACTIVE_CHUNKS = 1 << 0;
+        /// Lines along the borders between chunks.
+        const CHUNK_GRID = 1 << 1;
+        /// The view's legend, in the top left corner.
+        const LEGEND = 1 << 2;
+    }
+}
+
+/// Color of the active chunk outlines.
+pub const ACTIVE_COLOR: [u8; 4] = [255, 51, 51, 255];
+
+/// Color of the chunk grid lines.
+pub const GRID_COLOR: [u8; 4] = [90, 90, 110, 255];
+
+/// Pixels per font pixel of legends drawn over images.
+pub const LEGEND_SCALE: usize = 2;
+
+const LEGEND_BACKGROUND: [u8; 4] = [20, 20, 24, 255];
+const LEGEND_TEXT: [u8; 4] = [230, 230, 230, 255];
+
+/// Width and height of the font's characters, and the space between them.
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const GLYPH_SPACING: usize = 1;
+
+/// An RGBA image, stored row by row from the top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    /// Creates a transparent image.
+    pub fn new(width: usize, height: usize) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![[0; 4]; width * height],
+        }
+    }
+
+    /// Draws every cell of `world` as a `scale`×`scale` square in the colors
+    /// of `view`, with the bottom row of the world at the bottom of the
+    /// image, then draws the chosen overlays on top.
+    pub fn of_world(world: &World, scale: usize, view: View, overlays: Overlays) -> Self {
+        let whole = Rect {
+            x0: 0,
+            y0: 0,
+            x1: world.width,
+            y1: world.height,
+        };
+        Self::of_region(world, whole, scale, view, overlays)
+    }
+
+    /// Like [`Image::of_world`], but only draws the cells in `region`.
+    pub fn of_region(
+        world: &World,
+        region: Rect,
+        scale: usize,
+        view: View,
+        overlays: Overlays,
+    ) -> Self {
+        assert!(scale > 0, "scale must be at least 1");
+        assert!(
+            !region.is_empty() && region.x1 <= world.width && region.y1 <= world.height,
+            "region must be a non-empty part of the world",
+        );
+        let w = world.width;
+        let mut cells = vec![[0; 4]; w * world.height];
+        view.render_rect_to(world, region, &mut cells);
+
+        let (width, height) = (region.x1 - region.x0, region.y1 - region.y0);
+        let mut image = Image::new(width * scale, height * scale);
+        for (iy, row) in image.pixels.chunks_exact_mut(width * scale).enumerate() {
+            let y = region.y1 - 1 - iy / scale;
+            for (ix, pixel) in row.iter_mut().enumerate() {
+                *pixel = cells[y * w + region.x0 + ix / scale];
+            }
+        }
+
+        if overlays.contains(Overlays::CHUNK_GRID) {
+            let first = |start: usize| start.next_multiple_of(CHUNK_SIZE).max(CHUNK_SIZE);
+            for x in (first(region.x0)..region.x1).step_by(CHUNK_SIZE) {
+                image.fill((x - region.x0) * scale, 0, 1, height * scale, GRID_COLOR);
+            }
+            for y in (first(region.y0)..region.y1).step_by(CHUNK_SIZE) {
+                let iy = (region.y1 - y) * scale - 1;
+                image.fill(0, iy, width * scale, 1, GRID_COLOR);
+            }
+        }
+        if overlays.contains(Overlays::ACTIVE_CHUNKS) {
+            for rect in world.active_chunks() {
+                let rect = rect.intersect(region);
+                if !rect.is_empty() {
+                    image.outline(region_to_image(rect, region, scale), ACTIVE_COLOR);
+                }
+            }
+        }
+        if overlays.contains(Overlays::LEGEND) {
+            image.draw_image(&Image::of_legend(&view.legend(world), LEGEND_SCALE), 0, 0);
+        }
+        image
+    }
+
+    /// Draws the legend's title with a swatch and label for every entry
+    /// below it, each font pixel a `scale`×`scale` square.
+    pub fn of_legend(legend: &Legend, scale: usize) -> Self {
+        const PADDING: usize = 2;
+        const LINE: usize = GLYPH_HEIGHT + 2;
+        const LABEL_X: usize = PADDING + GLYPH_HEIGHT + 2;
+
+        let title_width = PADDING + text_width(&legend.title);
+        let width = (legend.entries.iter())
+            .map(|(_, label)| LABEL_X + text_width(label))
+            .fold(title_width, usize::max)
+            + PADDING;
+        let height = 2 * PADDING + LINE * (1 + legend.entries.len()) - 2;
+
+        let mut image = Image::new(width * scale, height * scale);
+        image.fill(0, 0, width * scale, height * scale, LEGEND_BACKGROUND);
+        image.draw_text(&legend.title, PADDING, PADDING, scale, LEGEND_TEXT);
+        for (line, (color, label)) in legend.entries.iter().enumerate() {
+            let y = PADDING + LINE * (line + 1);
+            let size = GLYPH_HEIGHT * scale;
+            image.fill(PADDING * scale, y * scale, size, size, *color);
+            image.draw_text(label, LABEL_X, y, scale, LEGEND_TEXT);
+        }
+        image
+    }
+
+    /// Copies `other` with its top left corner at `(x, y)`, cut off at the
+    /// edges.
+    pub fn draw_image(&mut self, other: &Image, x: usize, y: usize) {
+        for row in 0..other.height.min(self.height.saturating_sub(y)) {
+            let width = other.width.min(self.width.saturating_sub(x));
+            let from = row * other.width;
+            let to = (y + row) * self.width + x;
+            self.pixels[to..to + width].copy_from_slice(&other.pixels[from..from + width]);
+        }
+    }
+
+    /// Writes `text` in a small upper case font, with the top left corner at
+    /// font pixel `(x, y)` and each font pixel `scale`×`scale` pixels.
+    /// Characters the font lacks are drawn as `?`.
+    pub fn draw_text(&mut self, text: &str, x: usize, y: usize, scale: usize, color: [u8; 4]) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i * (GLYPH_WIDTH + GLYPH_SPACING);
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        let (px, py) = ((left + col) * scale, (y + row) * scale);
+                        self.fill(px, py, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fills a `width`×`height` block with its top left corner at `(x, y)`,
+    /// cut off at the edges.
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 4]) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                self.pixels[row * self.width + col] = color;
+            }
+        }
+    }
+
+    /// Draws a one pixel wide border just inside `rect`, given in pixels.
+    fn outline(&mut self, rect: Rect, color: [u8; 4]) {
+        let (width, height) = (rect.x1 - rect.x0, rect.y1 - rect.y0);
+        self.fill(rect.x0, rect.y0, width, 1, color);
+        self.fill(rect.x0, rect.y1 - 1, width, 1, color);
+        self.fill(rect.x0, rect.y0, 1, height, color);
+        self.fill(rect.x1 - 1, rect.y0, 1, height, color);
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), ImageError> {
+        let file = File::create(path).map_err(ImageError::Io)?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(ImageError::Encode)?;
+        writer
+            .write_image_data(bytemuck::cast_slice(&self.pixels))
+            .map_err(ImageError::Encode)
+    }
+
+    /// Loads an 8-bit RGBA PNG, like the ones [`Image::save_png`] writes.
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        let file = File::open(path).map_err(ImageError::Io)?;
+        let mut reader = png::Decoder::new(BufReader::new(file))
+            .read_info()
+            .map_err(ImageError::Decode)?;
+        let info = reader.info();
+        if (info.color_type, info.bit_depth) != (png::ColorType::Rgba, png::BitDepth::Eight) {
+            return Err(ImageError::Unsupported(info.color_type, info.bit_depth));
+        }
+        let (width, height) = (info.width as usize, info.height as usize);
+        let mut bytes = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut bytes).map_err(ImageError::Decode)?;
+        Ok(Image {
+            width,
+            height,
+            pixels: bytes[..width * height * 4]
+                .chunks_exact(4)
+                .map(|p| [p[0], p[1], p[2], p[3]])
+                .collect(),
+        })
+    }
+}
+
+/// Width in font pixels of `text`.
+fn text_width(text: &str) -> usize {
+    let len = text.chars().count();
+    (len * (GLYPH_WIDTH + GLYPH_SPACING)).saturating_sub(GLYPH_SPACING)
+}
+
+/// The rows of a character in a 3×5 font, top first, leftmost pixel in the
+/// highest bit.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+        ' ' => [0; GLYPH_HEIGHT],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '(' => [0b010, 0b100, 0b100, 0b100, 0b010],
+        ')' => [0b010, 0b001, 0b001, 0b001, 0b010],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+/// Converts a rectangle of cells into the pixels covering it in an image
+/// drawn at `scale` from the cells in `region`.
+fn region_to_image(rect: Rect, region: Rect, scale: usize) -> Rect {
+    Rect {
+        x0: (rect.x0 - region.x0) * scale,
+        y0: (region.y1 - rect.y1) * scale,
+        x1: (rect.x1 - region.x0) * scale,
+        y1: (region.y1 - rect.y0) * scale,
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    Encode(png::EncodingError),
+    Decode(png::DecodingError),
+    Unsupported(png::ColorType, png::BitDepth),
+    Gif(gif::EncodingError),
+    /// GIF frames can't be wider or taller than 65535 pixels.
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Io(err) => write!(f, "could not access file: {err}"),
+            ImageError::Encode(err) => write!(f, "could not encode PNG: {err}"),
+            ImageError::Decode(err) => write!(f, "could not decode PNG: {err}"),
+            ImageError::Unsupported(color, depth) => {
+                write!(f, "expected 8-bit RGBA, found {color:?} at {depth:?}")
+            }
+            ImageError::Gif(err) => write!(f, "could not encode GIF: {err}"),
+            ImageError::TooLarge { width, height } => {
+                write!(f, "{width}x{height} is too large for a GIF")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
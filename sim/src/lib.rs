@@ -0,0 +1,21 @@
+//! Headless falling-sand simulation, usable without a window or GPU.
+
+pub mod chunks;
+pub mod combustion;
+pub mod data;
+pub mod electricity;
+pub mod elements;
+pub mod gas;
+pub mod heat;
+pub mod image;
+pub mod moisture;
+pub mod motion;
+pub mod pressure;
+pub mod reactions;
+pub mod recording;
+pub mod scenes;
+pub mod support;
+pub mod swap_buffer;
+pub mod util;
+pub mod view;
+pub mod world;
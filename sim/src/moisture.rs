@@ -0,0 +1,91 @@
+//! Liquid soaking into porous elements, spreading through them and drying out.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    chunks::Chunk,
+    elements::{Attributes, ElementId, Elements},
+    world::{in_bounds, touches, SimRng},
+};
+
+/// Moisture gained by absorbing one liquid cell, and lost by giving one off.
+pub const MOISTURE_PER_CELL: u8 = 64;
+
+/// From this much moisture on a cell counts as wet: it no longer slides
+/// down diagonally, so wet piles hold steeper slopes.
+pub const WET: u8 = 32;
+
+/// How much darker a cell at full moisture is drawn, from 0 to 1.
+pub const WET_DARKENING: f32 = 0.45;
+
+/// Chance per tick that a cell soaks up a touching liquid cell.
+pub const ABSORB_CHANCE: f32 = 0.2;
+
+/// Lets absorbent cells soak up, share and give off moisture. Each cell
+/// deals with one random neighbour per tick. Liquid is only moved between
+/// cells and moisture, except for damp cells holding less than one cell's
+/// worth, which just dry off.
+pub fn update_moisture(elems: &Elements, rng: &mut SimRng, ew: &mut Chunk, w: usize, h: usize) {
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    for i in ew.indices() {
+        let Some(moisture) = elems[ew[i].id].moisture else {
+            continue;
+        };
+        // damp cells and cells next to their liquid may change on any tick
+        if ew[i].moisture > 0 || touches(ew, i, moisture.absorbs, w, h) {
+            ew.wake(i);
+        }
+        let (x, y) = ((i % w) as isize, (i / w) as isize);
+        let &(dx, dy) = directions.choose(rng).expect("directions are not empty");
+        if !in_bounds(x + dx, y + dy, w, h) {
+            continue;
+        }
+        let j = (y + dy) as usize * w + (x + dx) as usize;
+        let other = ew[j];
+
+        if other.id == moisture.absorbs {
+            if rng.gen::<f32>() >= ABSORB_CHANCE {
+                continue;
+            }
+            if let Some(amount) = ew[i].moisture.checked_add(MOISTURE_PER_CELL) {
+                if amount <= moisture.capacity {
+                    ew[i].moisture = amount;
+                    ew[j] = elems.create(ElementId::AIR, rng);
+                    ew.wake(j);
+                    continue;
+                }
+            }
+            if let Some(saturated) = moisture.saturates_to {
+                let temperature = ew[i].temperature;
+                ew[i] = elems.create(saturated, rng);
+                ew[i].temperature = temperature;
+                ew[j] = elems.create(ElementId::AIR, rng);
+                ew.wake(i);
+                ew.wake(j);
+            }
+        } else if let Some(other_moisture) = elems[other.id].moisture {
+            // move a quarter of the difference towards the drier cell
+            if other_moisture.absorbs == moisture.absorbs && ew[i].moisture > other.moisture {
+                let room = other_moisture.capacity.saturating_sub(other.moisture);
+                let amount = ((ew[i].moisture - other.moisture) / 4).min(room);
+                ew[i].moisture -= amount;
+                ew[j].moisture += amount;
+                // wetness decides whether cells slide
+                if amount > 0 {
+                    ew.wake(i);
+                }
+            }
+        } else if elems[other.id].has(Attributes::AIR) && ew[i].moisture > 0 {
+            let heat = (ew[i].temperature / 100.0).clamp(0.0, 1.0);
+            if rng.gen::<f32>() < moisture.drying * heat {
+                if ew[i].moisture >= MOISTURE_PER_CELL {
+                    ew[i].moisture -= MOISTURE_PER_CELL;
+                    ew[j] = elems.create(moisture.evaporates_to, rng);
+                } else {
+                    ew[i].moisture = 0;
+                }
+                ew.wake(j);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Per-cell velocity: gravity, moves of several cells per tick and collisions.
+
+use rand::Rng;
+
+use crate::{
+    chunks::Chunk,
+    elements::{Attributes, Cell, CellFlags, ElementDef, Elements},
+    world::{immovable, in_bounds, SimRng},
+};
+
+/// Velocity is stored in steps of 1/`VELOCITY_SCALE` cells per tick.
+pub const VELOCITY_SCALE: f32 = 16.0;
+
+/// Speed gained per tick while falling, in cells per tick.
+pub const GRAVITY: f32 = 0.25;
+
+/// Terminal speed in cells per tick.
+pub const MAX_SPEED: f32 = 6.0;
+
+/// Impacts slower than this, in cells per tick, don't splash. Keeps cells
+/// resting on the ground from creeping sideways.
+pub const SPLASH_SPEED: f32 = 1.0;
+
+/// Share of their sideways speed that liquids without viscosity keep every
+/// tick.
+pub const LIQUID_DRAG: f32 = 0.9;
+
+/// Share of their sideways speed that everything else keeps every tick.
+pub const SOLID_DRAG: f32 = 0.5;
+
+/// Furthest a liquid without viscosity spreads sideways in one tick.
+pub const MAX_FLOW_DISTANCE: usize = 4;
+
+/// Accelerates the falling cell at `(x, y)` and moves it along the line
+/// given by its velocity, one cell at a time, until it reaches the end or
+/// hits something it can't displace. On a hit, half the velocity is handed
+/// to the cell that was hit if it can move, and a vertical impact turns
+/// into sideways speed by the element's `splash`.
+///
+/// Cells always try to move down by at least one cell unless flung
+/// upwards, so slow cells still fall like they did before velocity existed.
+/// Returns the index the cell ended up at.
+pub(crate) fn move_ballistic(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut Chunk,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+) -> usize {
+    let start = y * w + x;
+    let def = &elems[ew[start].id];
+    let drag = if def.has(Attributes::LIQUID) {
+        LIQUID_DRAG * (1.0 - def.viscosity)
+    } else {
+        SOLID_DRAG
+    };
+
+    let [vx, vy] = ew[start].velocity();
+    let mut vx = vx * drag;
+    let mut vy = (vy - GRAVITY).max(-MAX_SPEED);
+    let sx = round_randomly(vx, rng);
+    let mut sy = round_randomly(vy, rng);
+    if vy <= 0.0 {
+        sy = sy.min(-1);
+    }
+
+    let steps = sx.abs().max(sy.abs());
+    let (mut px, mut py) = (x as isize, y as isize);
+    let mut hit = None;
+    for step in 1..=steps {
+        let t = step as f32 / steps as f32;
+        let nx = x as isize + (sx as f32 * t).round() as isize;
+        let ny = y as isize + (sy as f32 * t).round() as isize;
+        let vertical = ny != py;
+
+        if !in_bounds(nx, ny, w, h) {
+            hit = Some((None, vertical));
+            break;
+        }
+        let target = ny as usize * w + nx as usize;
+        // don't squeeze diagonally past the corner of an immovable cell
+        let corner = ny as usize * w + px as usize;
+        if !can_displace(elems, def, &ew[target])
+            || (vertical && nx != px && immovable(elems, &ew[corner]))
+        {
+            hit = Some((Some(target), vertical));
+            break;
+        }
+
+        ew.swap(py as usize * w + px as usize, target);
+        (px, py) = (nx, ny);
+    }
+
+    let end = py as usize * w + px as usize;
+    if let Some((other, vertical)) = hit {
+        if let Some(other) = other {
+            let other_cell = &mut ew[other];
+            if elems[other_cell.id].has(Attributes::CAN_FALL) && !other_cell.held() {
+                let [ox, oy] = other_cell.velocity();
+                other_cell.set_velocity([ox + vx / 2.0, oy + vy / 2.0]);
+                vx /= 2.0;
+                vy /= 2.0;
+            }
+        }
+        if vertical {
+            let speed = vy.abs();
+            if speed >= SPLASH_SPEED {
+                let side = if vx == 0.0 {
+                    if rng.gen() {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                } else {
+                    vx.signum()
+                };
+                vx += side * speed * def.splash;
+            }
+            vy = 0.0;
+        } else {
+            vx = 0.0;
+        }
+    }
+
+    let cell = &mut ew[end];
+    cell.set_velocity([vx, vy]);
+    cell.flags.set(CellFlags::FALLING, py < y as isize);
+    if cell.velocity() != [0.0; 2] {
+        ew.wake(end);
+    }
+    end
+}
+
+/// Lets the resting liquid cell at `(x, y)` spread sideways. It picks a
+/// random side it can flow to and, with chance `1 - viscosity`, moves up to
+/// `MAX_FLOW_DISTANCE * (1 - viscosity)` cells that way, at least one,
+/// stopping early above a gap it can fall into.
+/// Returns the index the cell ended up at, if it moved.
+pub(crate) fn flow_sideways(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut Chunk,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+) -> Option<usize> {
+    let def = &elems[ew[y * w + x].id];
+    let fluidity = 1.0 - def.viscosity;
+    let distance = ((MAX_FLOW_DISTANCE as f32 * fluidity).round() as usize).max(1);
+
+    let open = |ew: &Chunk, nx: isize, ny: isize| {
+        in_bounds(nx, ny, w, h) && can_displace(elems, def, &ew[ny as usize * w + nx as usize])
+    };
+    let sides = if rng.gen() { [-1, 1] } else { [1, -1] };
+    let dx = sides
+        .into_iter()
+        .find(|&dx| open(ew, x as isize + dx, y as isize))?;
+    if rng.gen::<f32>() >= fluidity {
+        // it may still flow on a later tick
+        ew.wake(y * w + x);
+        return None;
+    }
+
+    let mut px = x as isize;
+    for _ in 0..distance {
+        let nx = px + dx;
+        if !open(ew, nx, y as isize) {
+            break;
+        }
+        ew.swap(y * w + px as usize, y * w + nx as usize);
+        px = nx;
+        if open(ew, px, y as isize - 1) {
+            break;
+        }
+    }
+    Some(y * w + px as usize)
+}
+
+/// Whether a cell of the `mover` element can swap places with `target`.
+fn can_displace(elems: &Elements, mover: &ElementDef, target: &Cell) -> bool {
+    let other = &elems[target.id];
+    let both_solid = mover.has(Attributes::SOLID) && other.has(Attributes::SOLID);
+    let liquid_and_gas = mover.has(Attributes::LIQUID) && other.has(Attributes::GAS);
+
+    !immovable(elems, target) && !both_solid && !liquid_and_gas && other.density < mover.density
+}
+
+/// Rounds up or down at random, weighted by the fractional part, so that
+/// slow speeds still add up to the right distance over several ticks.
+fn round_randomly(v: f32, rng: &mut SimRng) -> i32 {
+    let floor = v.floor();
+    let up = rng.gen::<f32>() < v - floor;
+    floor as i32 + up as i32
+}
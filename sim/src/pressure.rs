@@ -0,0 +1,158 @@
+//! Liquid pressure: connected bodies of liquid settle to a common level.
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    chunks::{ChunkActivity, Rect},
+    elements::{Attributes, Cell, ElementDef, Elements},
+    world::{immovable, in_bounds, SimRng},
+};
+
+/// Reused buffers for [`update_pressure`].
+#[derive(Clone, Debug, Default)]
+pub struct PressureScratch {
+    /// Number of the last body that visited the cell. Bodies are numbered on
+    /// from one tick to the next, so these never need clearing.
+    visited: Vec<u32>,
+    /// Number of the last body that listed the cell as an outlet.
+    outlet_of: Vec<u32>,
+    /// Number of the last body, 0 before the first.
+    bodies: u32,
+    awake: Vec<Rect>,
+    stack: Vec<usize>,
+    surface: Vec<usize>,
+    outlets: Vec<usize>,
+}
+
+/// Moves liquid from the top of each body to its lowest outlet, as the
+/// weight of the column would push it. A body is a 4-connected group of
+/// cells of one liquid element, so liquids of different density keep
+/// layering. Surface cells are resting cells with something the liquid can
+/// displace above them. Outlets are neighbouring cells it can displace.
+///
+/// Each tick the highest surface cells swap with the lowest outlets, one
+/// pair at a time, as long as the outlet lies at least two rows lower. So
+/// U-tubes even out one layer per tick and liquid climbs pipes up to the
+/// level of its source. Viscous bodies only do so with chance
+/// `1 - viscosity` per tick.
+///
+/// Only bodies with an awake cell are looked at. Whatever could let a
+/// settled body flow wakes it, and a body that's only waiting on its
+/// viscosity is kept awake.
+pub fn update_pressure(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut [Cell],
+    activity: &mut ChunkActivity,
+    scratch: &mut PressureScratch,
+    w: usize,
+    h: usize,
+) {
+    let PressureScratch {
+        visited,
+        outlet_of,
+        bodies,
+        awake,
+        stack,
+        surface,
+        outlets,
+    } = scratch;
+    let len = ew.len();
+    if visited.len() != len || u32::MAX - *bodies < len as u32 {
+        visited.clear();
+        visited.resize(len, 0);
+        outlet_of.clear();
+        outlet_of.resize(len, 0);
+        *bodies = 0;
+    }
+    // cells visited by an earlier tick have a lower number
+    let first = *bodies + 1;
+
+    awake.clear();
+    awake.extend(activity.awake());
+    if awake.is_empty() {
+        return;
+    }
+    // bodies are found in grid order however the grid is split into chunks
+    let starts = (0..h).flat_map(|y| {
+        (awake.iter())
+            .filter(move |rect| (rect.y0..rect.y1).contains(&y))
+            .flat_map(move |rect| (rect.x0..rect.x1).map(move |x| y * w + x))
+    });
+
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    for start in starts {
+        let id = ew[start].id;
+        let def = &elems[id];
+        if visited[start] >= first || !def.has(Attributes::LIQUID) {
+            continue;
+        }
+        *bodies += 1;
+        let body = *bodies;
+        surface.clear();
+        outlets.clear();
+
+        visited[start] = body;
+        stack.push(start);
+        while let Some(i) = stack.pop() {
+            let (x, y) = ((i % w) as isize, (i / w) as isize);
+            for &(dx, dy) in &directions {
+                if !in_bounds(x + dx, y + dy, w, h) {
+                    continue;
+                }
+                let j = (y + dy) as usize * w + (x + dx) as usize;
+                if ew[j].id == id {
+                    if visited[j] < first {
+                        visited[j] = body;
+                        stack.push(j);
+                    }
+                } else if can_displace(elems, def, &ew[j]) {
+                    if dy == 1 && !ew[i].falling() {
+                        surface.push(i);
+                    }
+                    if outlet_of[j] != body {
+                        outlet_of[j] = body;
+                        outlets.push(j);
+                    }
+                }
+            }
+        }
+
+        if rng.gen::<f32>() < def.viscosity {
+            let top = surface.iter().max_by_key(|&&i| i / w);
+            let bottom = outlets.iter().map(|&i| i / w).min();
+            if let (Some(&top), Some(bottom)) = (top, bottom) {
+                if bottom + 1 < top / w {
+                    activity.wake_cell(top % w, top / w);
+                }
+            }
+            continue;
+        }
+        // shuffle before the stable sorts so ties don't favour one side
+        surface.shuffle(rng);
+        surface.sort_by_key(|&i| std::cmp::Reverse(i / w));
+        outlets.shuffle(rng);
+        outlets.sort_by_key(|&i| i / w);
+
+        for (&from, &to) in surface.iter().zip(outlets.iter()) {
+            if to / w + 1 >= from / w {
+                break;
+            }
+            ew.swap(from, to);
+            activity.wake_cell(from % w, from / w);
+            activity.wake_cell(to % w, to / w);
+            // neither cell moves again this tick
+            visited[from] = body;
+            visited[to] = body;
+        }
+    }
+}
+
+/// Whether the liquid can flow into the cell, pushing its content aside.
+fn can_displace(elems: &Elements, liquid: &ElementDef, target: &Cell) -> bool {
+    let other = &elems[target.id];
+    !immovable(elems, target)
+        && !other.has(Attributes::SOLID)
+        && !other.has(Attributes::GAS)
+        && other.density < liquid.density
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+use crate::{
+    data::{parse_reactions, DEFAULT_REACTIONS},
+    elements::{ElementId, DEF_ELEMS},
+};
+
+lazy_static! {
+    /// The built-in reactions, resolved against [`DEF_ELEMS`].
+    pub static ref REACTIONS: Reactions = parse_reactions(DEFAULT_REACTIONS, &DEF_ELEMS)
+        .expect("built-in reaction definitions are invalid");
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReactionKey(ElementId, ElementId);
+
+impl ReactionKey {
+    pub fn new(a: ElementId, b: ElementId) -> Self {
+        let (min, max) = if a <= b { (a, b) } else { (b, a) };
+        ReactionKey(min, max)
+    }
+}
+
+/// Reaction rules keyed by unordered pairs of element ids.
+#[derive(Clone, Debug, Default)]
+pub struct Reactions {
+    table: HashMap<ReactionKey, Vec<ChemicalReaction>>,
+    /// Whether `table` has rules for a pair, indexed by both ids, so the
+    /// chemistry pass can skip the hash lookup for pairs that don't react.
+    reacts: Vec<bool>,
+}
+
+impl Reactions {
+    pub fn insert(&mut self, reaction: ChemicalReaction) {
+        let (a, b) = reaction.reactants;
+        self.reacts.resize(PAIRS, false);
+        self.reacts[pair_index(a, b)] = true;
+        self.reacts[pair_index(b, a)] = true;
+        self.table
+            .entry(ReactionKey::new(a, b))
+            .or_default()
+            .push(reaction);
+    }
+
+    /// All rules between the two elements, in definition order.
+    pub fn get(&self, a: ElementId, b: ElementId) -> &[ChemicalReaction] {
+        if !self.reacts.get(pair_index(a, b)).copied().unwrap_or(false) {
+            return &[];
+        }
+        self.table
+            .get(&ReactionKey::new(a, b))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Number of ordered pairs of element ids.
+const PAIRS: usize = 1 << 16;
+
+fn pair_index(a: ElementId, b: ElementId) -> usize {
+    (a.0 as usize) << 8 | b.0 as usize
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChemicalReaction {
+    pub reactants: (ElementId, ElementId),
+    /// What each reactant turns into, in the same order as `reactants`.
+    pub products: (ElementId, ElementId),
+    /// Chance per tick that touching reactants react.
+    pub probability: f32,
+    /// An element that has to touch one of the reactants.
+    pub catalyst: Option<ElementId>,
+    /// Bounds on the mean temperature of the two reactants.
+    pub min_temperature: Option<f32>,
+    pub max_temperature: Option<f32>,
+}
+
+impl ChemicalReaction {
+    pub fn temperature_ok(&self, temperature: f32) -> bool {
+        self.min_temperature.is_none_or(|min| temperature >= min)
+            && self.max_temperature.is_none_or(|max| temperature <= max)
+    }
+}
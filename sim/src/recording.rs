@@ -0,0 +1,160 @@
+//! Recording every few ticks of the world into a PNG sequence or an animated
+//! GIF, for clips of how the simulation behaves.
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    chunks::Rect,
+    image::{Image, ImageError, Overlays},
+    view::View,
+    world::World,
+};
+
+/// How hard the GIF encoder works on picking each frame's palette, from 1
+/// (best) to 30 (fastest).
+pub const GIF_QUANTIZE_SPEED: i32 = 10;
+
+/// What a recording captures and how it's drawn.
+#[derive(Clone, Copy, Debug)]
+pub struct RecordOptions {
+    /// Captures one tick out of this many, starting with the first.
+    pub every: usize,
+    /// Pixels per cell, see [`Image::of_world`].
+    pub scale: usize,
+    /// Part of the grid to record, or all of it.
+    pub crop: Option<Rect>,
+    pub view: View,
+    pub overlays: Overlays,
+    /// Ticks per second the recording is played back at, for GIFs.
+    pub ticks_per_second: u32,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        RecordOptions {
+            every: 1,
+            scale: 1,
+            crop: None,
+            view: View::Normal,
+            overlays: Overlays::empty(),
+            ticks_per_second: 60,
+        }
+    }
+}
+
+enum Output {
+    /// Numbered PNG files in a directory.
+    Frames(PathBuf),
+    Gif(Option<gif::Encoder<BufWriter<File>>>, PathBuf),
+}
+
+/// Writes frames of the world as it changes. Call [`Recorder::record`]
+/// after every tick and [`Recorder::finish`] once done.
+pub struct Recorder {
+    options: RecordOptions,
+    output: Output,
+    ticks: usize,
+    frames: usize,
+}
+
+impl Recorder {
+    /// Starts a recording to `path`: an animated GIF if it ends in `.gif`,
+    /// otherwise a directory of numbered PNG files. Missing directories are
+    /// created.
+    pub fn new(path: impl AsRef<Path>, options: RecordOptions) -> Result<Self, ImageError> {
+        assert!(options.every > 0, "must record at least every tick");
+        let path = path.as_ref();
+        let gif = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+        let output = if gif {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(ImageError::Io)?;
+            }
+            // the encoder needs the frame size, so it's created with the first
+            Output::Gif(None, path.to_path_buf())
+        } else {
+            fs::create_dir_all(path).map_err(ImageError::Io)?;
+            Output::Frames(path.to_path_buf())
+        };
+        Ok(Recorder {
+            options,
+            output,
+            ticks: 0,
+            frames: 0,
+        })
+    }
+
+    /// Counts a tick, capturing the world if it's one of the recorded ones.
+    pub fn record(&mut self, world: &World) -> Result<(), ImageError> {
+        let tick = self.ticks;
+        self.ticks += 1;
+        if !tick.is_multiple_of(self.options.every) {
+            return Ok(());
+        }
+
+        let RecordOptions {
+            scale,
+            crop,
+            view,
+            overlays,
+            ..
+        } = self.options;
+        let image = match crop {
+            Some(region) => Image::of_region(world, region, scale, view, overlays),
+            None => Image::of_world(world, scale, view, overlays),
+        };
+        let delay = self.frame_delay();
+        match &mut self.output {
+            Output::Frames(dir) => {
+                image.save_png(dir.join(format!("frame_{:05}.png", self.frames)))?;
+            }
+            Output::Gif(encoder, path) => {
+                let (Ok(width), Ok(height)) = (image.width.try_into(), image.height.try_into())
+                else {
+                    return Err(ImageError::TooLarge {
+                        width: image.width,
+                        height: image.height,
+                    });
+                };
+                let encoder = match encoder {
+                    Some(encoder) => encoder,
+                    None => {
+                        let file = File::create(&*path).map_err(ImageError::Io)?;
+                        let mut new = gif::Encoder::new(BufWriter::new(file), width, height, &[])
+                            .map_err(ImageError::Gif)?;
+                        new.set_repeat(gif::Repeat::Infinite)
+                            .map_err(ImageError::Gif)?;
+                        encoder.insert(new)
+                    }
+                };
+                let mut pixels: Vec<u8> = bytemuck::cast_slice(&image.pixels).to_vec();
+                let mut frame =
+                    gif::Frame::from_rgba_speed(width, height, &mut pixels, GIF_QUANTIZE_SPEED);
+                frame.delay = delay;
+                encoder.write_frame(&frame).map_err(ImageError::Gif)?;
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Finishes writing and returns the number of frames recorded.
+    pub fn finish(self) -> Result<usize, ImageError> {
+        if let Output::Gif(Some(encoder), _) = self.output {
+            let mut file = encoder.into_inner().map_err(ImageError::Io)?;
+            file.flush().map_err(ImageError::Io)?;
+        }
+        Ok(self.frames)
+    }
+
+    /// How long each GIF frame is shown, in hundredths of a second.
+    fn frame_delay(&self) -> u16 {
+        let seconds = self.options.every as f32 / self.options.ticks_per_second as f32;
+        (seconds * 100.0).round().clamp(1.0, u16::MAX as f32) as u16
+    }
+}
@@ -0,0 +1,185 @@
+//! Small worlds built in code, which the headless runner renders and records
+//! and which are checked against the golden images in `sim/golden`.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    image::{Image, Overlays},
+    view::View,
+    world::World,
+};
+
+/// Where the golden images are kept.
+pub const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden");
+
+/// Seed every scene starts from.
+pub const SEED: u64 = 1;
+
+/// Pixels per cell in golden images and, by default, rendered scenes.
+pub const SCALE: usize = 4;
+
+/// A world built in code and run for a fixed number of ticks.
+pub struct Scene {
+    pub name: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub ticks: usize,
+    pub view: View,
+    pub overlays: Overlays,
+    build: fn(&mut World),
+}
+
+pub const SCENES: &[Scene] = &[
+    Scene {
+        name: "sand_pile",
+        width: 64,
+        height: 64,
+        ticks: 200,
+        view: View::Normal,
+        overlays: Overlays::empty(),
+        build: |world| {
+            fill(world, "Stone", 0, 0, 64, 3);
+            fill(world, "Sand", 24, 40, 40, 60);
+        },
+    },
+    Scene {
+        name: "liquids",
+        width: 96,
+        height: 64,
+        ticks: 300,
+        view: View::Normal,
+        overlays: Overlays::empty(),
+        build: |world| {
+            fill(world, "Stone", 8, 0, 88, 3);
+            fill(world, "Stone", 8, 3, 11, 40);
+            fill(world, "Stone", 85, 3, 88, 40);
+            fill(world, "Water", 16, 30, 36, 50);
+            fill(world, "Oil", 44, 30, 60, 45);
+            fill(world, "Honey", 68, 30, 80, 50);
+        },
+    },
+    Scene {
+        name: "fire",
+        width: 128,
+        height: 64,
+        ticks: 150,
+        view: View::Normal,
+        overlays: Overlays::ACTIVE_CHUNKS.union(Overlays::CHUNK_GRID),
+        build: |world| {
+            fill(world, "Stone", 0, 0, 128, 3);
+            fill(world, "Wood", 48, 3, 80, 20);
+            fill(world, "Fire", 48, 20, 80, 21);
+        },
+    },
+    Scene {
+        name: "lava",
+        width: 64,
+        height: 64,
+        ticks: 200,
+        view: View::Normal,
+        overlays: Overlays::empty(),
+        build: |world| {
+            fill(world, "Stone", 0, 0, 64, 3);
+            fill(world, "Ice", 16, 3, 48, 15);
+            fill(world, "Lava", 24, 40, 40, 50);
+        },
+    },
+    Scene {
+        name: "lava_heat",
+        width: 64,
+        height: 64,
+        ticks: 200,
+        view: View::Temperature,
+        overlays: Overlays::LEGEND,
+        build: |world| {
+            fill(world, "Stone", 0, 0, 64, 3);
+            fill(world, "Ice", 16, 3, 48, 15);
+            fill(world, "Lava", 24, 40, 40, 50);
+        },
+    },
+];
+
+impl Scene {
+    /// The scene by name, or an error listing the scenes there are.
+    pub fn find(name: &str) -> Result<&'static Scene, String> {
+        SCENES
+            .iter()
+            .find(|scene| scene.name == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = SCENES.iter().map(|scene| scene.name).collect();
+                format!(
+                    "unknown scene `{name}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+
+    pub fn build(&self) -> World {
+        let mut world = World::with_seed(self.width, self.height, SEED);
+        fill(&mut world, "Air", 0, 0, self.width, self.height);
+        (self.build)(&mut world);
+        world
+    }
+
+    /// Builds the scene and runs it for `ticks` ticks.
+    pub fn run(&self, ticks: usize) -> World {
+        let mut world = self.build();
+        for _ in 0..ticks {
+            world.update(0.0);
+        }
+        world
+    }
+
+    /// The scene after all its ticks, drawn like its golden image.
+    pub fn image(&self) -> Image {
+        let world = self.run(self.ticks);
+        Image::of_world(&world, SCALE, self.view, self.overlays)
+    }
+
+    pub fn golden_path(&self) -> PathBuf {
+        Path::new(GOLDEN_DIR).join(format!("{}.png", self.name))
+    }
+
+    /// Runs the scene and compares it with its golden image. If they differ,
+    /// what it got is written next to it as `<scene>.actual.png`.
+    pub fn check_golden(&self) -> Result<(), String> {
+        let image = self.image();
+        let path = self.golden_path();
+        let problem = match Image::load_png(&path) {
+            Ok(expected) if expected == image => return Ok(()),
+            Ok(expected) if (expected.width, expected.height) != (image.width, image.height) => {
+                format!(
+                    "size changed from {}x{} to {}x{}",
+                    expected.width, expected.height, image.width, image.height
+                )
+            }
+            Ok(expected) => {
+                let differing = (expected.pixels.iter())
+                    .zip(&image.pixels)
+                    .filter(|(a, b)| a != b)
+                    .count();
+                format!("{differing} pixels differ")
+            }
+            Err(err) => format!("{}: {err}", path.display()),
+        };
+        let actual = path.with_extension("actual.png");
+        Err(match image.save_png(&actual) {
+            Ok(()) => format!("{problem}\n  wrote {}", actual.display()),
+            Err(err) => format!("{problem}\n  could not write {}: {err}", actual.display()),
+        })
+    }
+}
+
+/// Fills the cells from `(x0, y0)` up to `(x1, y1)` with an element.
+pub fn fill(world: &mut World, name: &str, x0: usize, y0: usize, x1: usize, y1: usize) {
+    let id = world
+        .elements
+        .id(name)
+        .unwrap_or_else(|| panic!("no element named {name}"));
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let i = y * world.width + x;
+            world.cells.r[i] = world.create(id);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Structural support for pillar-like elements.
+
+use rand::Rng;
+
+use crate::{
+    chunks::ChunkActivity,
+    elements::{Attributes, Cell, CellFlags, Elements},
+    world::{in_bounds, SimRng},
+};
+
+/// Marks pillar-like cells connected to the floor or to an immovable cell as
+/// held in place, and lets cut off cells break loose at their element's
+/// `pillar_like` chance.
+///
+/// Support is found by a flood fill through pillar-like cells, starting from
+/// those on the bottom row or touching an immovable element. Anything that
+/// could change it wakes a pillar-like cell, so while none is awake the
+/// fill is skipped.
+#[allow(clippy::too_many_arguments)]
+pub fn update_support(
+    elems: &Elements,
+    rng: &mut SimRng,
+    ew: &mut [Cell],
+    activity: &mut ChunkActivity,
+    supported: &mut Vec<bool>,
+    stack: &mut Vec<usize>,
+    w: usize,
+    h: usize,
+) {
+    let pillar = |cell: &Cell| elems[cell.id].pillar_like.is_some();
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    let awake = activity.awake().any(|rect| {
+        (rect.y0..rect.y1).any(|y| ew[y * w + rect.x0..y * w + rect.x1].iter().any(pillar))
+    });
+    if !awake {
+        return;
+    }
+
+    supported.clear();
+    supported.resize(ew.len(), false);
+    stack.clear();
+
+    for y in 0..h {
+        for x in 0..w {
+            let i = y * w + x;
+            if !pillar(&ew[i]) {
+                continue;
+            }
+            let anchored = y == 0
+                || directions.iter().any(|&(dx, dy)| {
+                    let nx = x as isize + dx;
+                    let ny = y as isize + dy;
+                    in_bounds(nx, ny, w, h)
+                        && elems[ew[ny as usize * w + nx as usize].id].has(Attributes::IMMOVABLE)
+                });
+            if anchored {
+                supported[i] = true;
+                stack.push(i);
+            }
+        }
+    }
+
+    while let Some(i) = stack.pop() {
+        let (x, y) = ((i % w) as isize, (i / w) as isize);
+        for &(dx, dy) in &directions {
+            if !in_bounds(x + dx, y + dy, w, h) {
+                continue;
+            }
+            let j = (y + dy) as usize * w + (x + dx) as usize;
+            if !supported[j] && pillar(&ew[j]) {
+                supported[j] = true;
+                stack.push(j);
+            }
+        }
+    }
+
+    for (i, cell) in ew.iter_mut().enumerate() {
+        let Some(chance) = elems[cell.id].pillar_like else {
+            continue;
+        };
+        if supported[i] {
+            if !cell.held() {
+                cell.flags.insert(CellFlags::HELD);
+                activity.wake_cell(i % w, i / w);
+            }
+        } else if cell.held() {
+            if rng.gen::<f32>() < chance {
+                cell.flags.remove(CellFlags::HELD);
+            }
+            activity.wake_cell(i % w, i / w);
+        }
+    }
+}
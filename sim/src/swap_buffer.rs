@@ -1,5 +1,4 @@
 pub struct SwapBuffer<T> {
-    width: usize,
     pub r: Vec<T>,
     pub w: Vec<T>,
 }
@@ -17,9 +16,8 @@ impl<T: Sync + Send + Clone> SwapBuffer<T> {
 }
 
 impl<T: Clone> SwapBuffer<T> {
-    pub fn from_arr(base: Vec<T>, width: usize) -> SwapBuffer<T> {
+    pub fn from_arr(base: Vec<T>) -> SwapBuffer<T> {
         SwapBuffer {
-            width,
             r: base.clone(),
             w: base,
         }
@@ -79,10 +79,10 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy> Point<T> {
 
 impl Point<i32> {
     pub fn clamp_usize(&self, max: Point<usize>) -> Point<usize> {
-        return Point {
+        Point {
             x: (self.x.max(0) as usize).min(max.x),
             y: (self.y.max(0) as usize).min(max.y),
-        };
+        }
     }
 }
 
@@ -205,57 +205,57 @@ impl<T: Mul<Output = T> + Copy> MulAssign<T> for Point<T> {
     }
 }
 
-impl Into<Point<f32>> for Point<usize> {
-    fn into(self) -> Point<f32> {
-        return Point {
-            x: self.x as f32,
-            y: self.y as f32,
-        };
+impl From<Point<usize>> for Point<f32> {
+    fn from(p: Point<usize>) -> Self {
+        Point {
+            x: p.x as f32,
+            y: p.y as f32,
+        }
     }
 }
 
-impl Into<Point<i32>> for Point<f32> {
-    fn into(self) -> Point<i32> {
-        return Point {
-            x: self.x as i32,
-            y: self.y as i32,
-        };
+impl From<Point<f32>> for Point<i32> {
+    fn from(p: Point<f32>) -> Self {
+        Point {
+            x: p.x as i32,
+            y: p.y as i32,
+        }
     }
 }
 
-impl Into<Point<u32>> for Point<i32> {
-    fn into(self) -> Point<u32> {
-        return Point {
-            x: self.x as u32,
-            y: self.y as u32,
-        };
+impl From<Point<i32>> for Point<u32> {
+    fn from(p: Point<i32>) -> Self {
+        Point {
+            x: p.x as u32,
+            y: p.y as u32,
+        }
     }
 }
 
-impl Into<Point<usize>> for Point<i32> {
-    fn into(self) -> Point<usize> {
-        return Point {
-            x: self.x as usize,
-            y: self.y as usize,
-        };
+impl From<Point<i32>> for Point<usize> {
+    fn from(p: Point<i32>) -> Self {
+        Point {
+            x: p.x as usize,
+            y: p.y as usize,
+        }
     }
 }
 
-impl Into<Point<usize>> for Point<f32> {
-    fn into(self) -> Point<usize> {
-        return Point {
-            x: self.x as usize,
-            y: self.y as usize,
-        };
+impl From<Point<f32>> for Point<usize> {
+    fn from(p: Point<f32>) -> Self {
+        Point {
+            x: p.x as usize,
+            y: p.y as usize,
+        }
     }
 }
 
-impl Into<Point<f32>> for Point<i32> {
-    fn into(self) -> Point<f32> {
-        return Point {
-            x: self.x as f32,
-            y: self.y as f32,
-        };
+impl From<Point<i32>> for Point<f32> {
+    fn from(p: Point<i32>) -> Self {
+        Point {
+            x: p.x as f32,
+            y: p.y as f32,
+        }
     }
 }
 
@@ -0,0 +1,277 @@
+//! Debug views that color cells by one property instead of their looks, each
+//! with a legend explaining the colors.
+
+use crate::{
+    chunks::Rect,
+    elements::{Cell, ElementId, Elements},
+    motion::MAX_SPEED,
+    world::World,
+};
+
+/// Densities at the ends of the density view's scale, which is logarithmic.
+pub const DENSITY_RANGE: (f32, f32) = (0.01, 10.0);
+
+/// Temperatures at the ends of the temperature view's scale, in °C.
+pub const TEMPERATURE_RANGE: (f32, f32) = (-100.0, 1500.0);
+
+/// Most elements the element view's legend lists.
+pub const MAX_LEGEND_ELEMENTS: usize = 16;
+
+const HEAT_RAMP: [[f32; 3]; 5] = [
+    [0.0, 0.0, 0.05],
+    [0.3, 0.05, 0.55],
+    [0.8, 0.15, 0.25],
+    [1.0, 0.6, 0.05],
+    [1.0, 1.0, 0.8],
+];
+
+const DENSITY_RAMP: [[f32; 3]; 4] = [
+    [0.02, 0.02, 0.1],
+    [0.1, 0.3, 0.6],
+    [0.3, 0.75, 0.75],
+    [0.95, 0.95, 0.8],
+];
+
+const NOT_ABSORBENT: [u8; 4] = [40, 40, 40, 255];
+const DRY: [f32; 3] = [0.6, 0.47, 0.3];
+const WET: [f32; 3] = [0.15, 0.35, 0.9];
+
+const STILL: [u8; 4] = [50, 50, 50, 255];
+
+const CHANGED: [u8; 4] = [255, 255, 255, 255];
+const UPDATED: [u8; 4] = [40, 60, 140, 255];
+const ASLEEP: [u8; 4] = [0, 0, 0, 255];
+
+/// What the color of each cell shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum View {
+    /// The colors the game is drawn in.
+    #[default]
+    Normal,
+    /// The element's density.
+    Density,
+    Temperature,
+    /// Absorbed liquid, for elements that can hold any.
+    Moisture,
+    /// Direction and speed of moving cells.
+    Velocity,
+    /// A distinct color for every element.
+    Element,
+    /// Which cells changed in the last tick, and which it updated.
+    Changed,
+}
+
+impl View {
+    pub const ALL: [View; 7] = [
+        View::Normal,
+        View::Density,
+        View::Temperature,
+        View::Moisture,
+        View::Velocity,
+        View::Element,
+        View::Changed,
+    ];
+
+    /// The view after this one, wrapping around.
+    pub fn next(self) -> View {
+        let i = View::ALL.iter().position(|&view| view == self).unwrap_or(0);
+        View::ALL[(i + 1) % View::ALL.len()]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            View::Normal => "normal",
+            View::Density => "density",
+            View::Temperature => "temperature",
+            View::Moisture => "moisture",
+            View::Velocity => "velocity",
+            View::Element => "element",
+            View::Changed => "changed",
+        }
+    }
+
+    /// The view with the given name, see [`View::name`].
+    pub fn from_name(name: &str) -> Option<View> {
+        View::ALL.into_iter().find(|view| view.name() == name)
+    }
+
+    /// Writes the colors of the cells in `rect` into `buf`, which holds one
+    /// color per cell of the whole grid, row by row.
+    pub fn render_rect_to(self, world: &World, rect: Rect, buf: &mut [[u8; 4]]) {
+        if self == View::Normal {
+            world.render_rect_to(rect, buf);
+            return;
+        }
+        let elems = &world.elements;
+        let cells = &world.cells.r;
+        let active: Vec<Rect> = match self {
+            View::Changed => world.active_chunks().collect(),
+            _ => Vec::new(),
+        };
+        for y in rect.y0..rect.y1 {
+            for x in rect.x0..rect.x1 {
+                let i = y * world.width + x;
+                let cell = &cells[i];
+                buf[i] = match self {
+                    View::Normal => unreachable!(),
+                    View::Density => density_color(elems[cell.id].density),
+                    View::Temperature => temperature_color(cell.temperature),
+                    View::Moisture => match elems[cell.id].moisture {
+                        Some(_) => moisture_color(cell.moisture as f32 / u8::MAX as f32),
+                        None => NOT_ABSORBENT,
+                    },
+                    View::Velocity => velocity_color(cell.velocity()),
+                    View::Element => element_color(cell.id),
+                    View::Changed => {
+                        let old = &world.previous_cells()[i];
+                        let looks = |cell: &Cell| cell.render_rgba(world.color_of(cell));
+                        if old.id != cell.id || looks(old) != looks(cell) {
+                            CHANGED
+                        } else if active.iter().any(|r| r.contains(x, y)) {
+                            UPDATED
+                        } else {
+                            ASLEEP
+                        }
+                    }
+                };
+            }
+        }
+    }
+
+    /// Explains the colors of the view. The element view lists the
+    /// elements in `world`.
+    pub fn legend(self, world: &World) -> Legend {
+        let entries = match self {
+            View::Normal => Vec::new(),
+            View::Density => {
+                let (min, max) = DENSITY_RANGE;
+                (0..4)
+                    .map(|i| {
+                        let density = min * (max / min).powf(i as f32 / 3.0);
+                        (density_color(density), format!("{density:.2}"))
+                    })
+                    .collect()
+            }
+            View::Temperature => {
+                let (min, max) = TEMPERATURE_RANGE;
+                (0..5)
+                    .map(|i| {
+                        let temperature = min + (max - min) * i as f32 / 4.0;
+                        (temperature_color(temperature), format!("{temperature} C"))
+                    })
+                    .collect()
+            }
+            View::Moisture => vec![
+                (NOT_ABSORBENT, "not absorbent".to_string()),
+                (moisture_color(0.0), "dry".to_string()),
+                (moisture_color(0.5), "half".to_string()),
+                (moisture_color(1.0), "full".to_string()),
+            ],
+            View::Velocity => [
+                ([0.0, 0.0], "still"),
+                ([MAX_SPEED, 0.0], "right"),
+                ([0.0, MAX_SPEED], "up"),
+                ([-MAX_SPEED, 0.0], "left"),
+                ([0.0, -MAX_SPEED], "down"),
+                ([MAX_SPEED / 4.0, 0.0], "slow right"),
+            ]
+            .into_iter()
+            .map(|(velocity, label)| (velocity_color(velocity), label.to_string()))
+            .collect(),
+            View::Element => element_entries(&world.elements, &world.cells.r),
+            View::Changed => vec![
+                (CHANGED, "changed".to_string()),
+                (UPDATED, "updated".to_string()),
+                (ASLEEP, "asleep".to_string()),
+            ],
+        };
+        Legend {
+            title: self.name().to_string(),
+            entries,
+        }
+    }
+}
+
+/// A view's title and a color swatch with a label for each thing it shows.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Legend {
+    pub title: String,
+    pub entries: Vec<([u8; 4], String)>,
+}
+
+/// The elements present in `cells`, in the order they're defined.
+fn element_entries(elems: &Elements, cells: &[Cell]) -> Vec<([u8; 4], String)> {
+    let mut present = vec![false; elems.len()];
+    for cell in cells {
+        present[cell.id.0 as usize] = true;
+    }
+    let mut entries: Vec<_> = elems
+        .iter()
+        .filter(|(id, _)| present[id.0 as usize])
+        .map(|(id, def)| (element_color(id), def.name.to_string()))
+        .collect();
+    if entries.len() > MAX_LEGEND_ELEMENTS {
+        let more = entries.len() - (MAX_LEGEND_ELEMENTS - 1);
+        entries.truncate(MAX_LEGEND_ELEMENTS - 1);
+        entries.push((ASLEEP, format!("+{more} more")));
+    }
+    entries
+}
+
+fn density_color(density: f32) -> [u8; 4] {
+    let (min, max) = DENSITY_RANGE;
+    ramp(&DENSITY_RAMP, (density / min).ln() / (max / min).ln())
+}
+
+fn temperature_color(temperature: f32) -> [u8; 4] {
+    let (min, max) = TEMPERATURE_RANGE;
+    ramp(&HEAT_RAMP, (temperature - min) / (max - min))
+}
+
+fn moisture_color(wetness: f32) -> [u8; 4] {
+    to_rgba(std::array::from_fn(|c| {
+        DRY[c] + (WET[c] - DRY[c]) * wetness
+    }))
+}
+
+/// Hue by direction, brightness by speed.
+fn velocity_color([vx, vy]: [f32; 2]) -> [u8; 4] {
+    if vx == 0.0 && vy == 0.0 {
+        return STILL;
+    }
+    let hue = vy.atan2(vx).rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU;
+    let speed = (vx.hypot(vy) / MAX_SPEED).min(1.0);
+    hsv(hue, 0.8, 0.35 + 0.65 * speed)
+}
+
+/// Spreads the hues of consecutive elements apart by the golden ratio, so
+/// each stands out from those defined next to it. Air is black.
+fn element_color(id: ElementId) -> [u8; 4] {
+    if id == ElementId::AIR {
+        return ASLEEP;
+    }
+    let hue = (id.0 as f32 * 0.618_034).fract();
+    hsv(hue, 0.65, 0.95)
+}
+
+/// Interpolates between evenly spaced colors at `t` from 0 to 1.
+fn ramp(stops: &[[f32; 3]], t: f32) -> [u8; 4] {
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let i = (t as usize).min(stops.len() - 2);
+    let f = t - i as f32;
+    to_rgba(std::array::from_fn(|c| {
+        stops[i][c] + (stops[i + 1][c] - stops[i][c]) * f
+    }))
+}
+
+fn hsv(hue: f32, saturation: f32, value: f32) -> [u8; 4] {
+    to_rgba(std::array::from_fn(|c| {
+        let k = (hue * 6.0 + [5.0, 3.0, 1.0][c]) % 6.0;
+        value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0)
+    }))
+}
+
+fn to_rgba(rgb: [f32; 3]) -> [u8; 4] {
+    let [r, g, b] = rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8);
+    [r, g, b, 255]
+}
@@ -0,0 +1,570 @@
+use super::{
+    chunks::{update_chunks, Chunk, ChunkActivity, Rect},
+    combustion::update_combustion,
+    electricity::update_electricity,
+    elements::{Attributes, Cell, CellFlags, ElementColor, ElementId, Elements, DEF_ELEMS},
+    gas::{update_gas_flow, WindField},
+    heat::{update_heat, update_phases, Boundary, AMBIENT_TEMPERATURE},
+    moisture::{update_moisture, WET},
+    motion::{flow_sideways, move_ballistic},
+    pressure::{update_pressure, PressureScratch},
+    reactions::{Reactions, REACTIONS},
+    support::update_support,
+    swap_buffer::SwapBuffer,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+/// The generator behind every stochastic decision in the simulation.
+/// ChaCha is used because its output is stable across platforms and versions.
+pub type SimRng = ChaCha8Rng;
+
+pub struct World {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub rng: SimRng,
+    pub elements: Elements,
+    pub reactions: Reactions,
+    pub boundary: Boundary,
+    pub cells: SwapBuffer<Cell>,
+    pub wind: WindField,
+    /// Updates chunks on the rayon thread pool. The cells come out the same
+    /// either way, so this can be turned off to compare or debug.
+    pub parallel: bool,
+    activity: ChunkActivity,
+    /// Scratch space for the heat pass.
+    temps: Vec<f32>,
+    /// Scratch space for the electricity pass.
+    charges: Vec<u8>,
+    /// Scratch space for the support pass.
+    supported: Vec<bool>,
+    stack: Vec<usize>,
+    pressure: PressureScratch,
+}
+
+impl World {
+    /// Creates a world with a random seed; see [`World::with_seed`].
+    pub fn new(width: usize, height: usize) -> World {
+        Self::with_seed(width, height, rand::random())
+    }
+
+    /// Creates a world whose every tick is reproducible: the same seed and
+    /// the same sequence of edits always produce identical cells.
+    pub fn with_seed(width: usize, height: usize, seed: u64) -> World {
+        Self::with_elements(width, height, seed, DEF_ELEMS.clone(), REACTIONS.clone())
+    }
+
+    /// Creates a seeded world using the given element and reaction definitions.
+    pub fn with_elements(
+        width: usize,
+        height: usize,
+        seed: u64,
+        elements: Elements,
+        reactions: Reactions,
+    ) -> World {
+        let mut rng = SimRng::seed_from_u64(seed);
+        let dirt = elements.id("Dirt").unwrap_or(ElementId::AIR);
+
+        let cells = (0..width * height)
+            .map(|_| {
+                if rng.gen_range(0..7) < 2 {
+                    elements.create(dirt, &mut rng)
+                } else {
+                    elements.create(ElementId::AIR, &mut rng)
+                }
+            })
+            .collect::<Vec<Cell>>();
+
+        World {
+            width,
+            height,
+            seed,
+            rng,
+            elements,
+            reactions,
+            boundary: Boundary::Fixed(AMBIENT_TEMPERATURE),
+            cells: SwapBuffer::from_arr(cells),
+            wind: WindField::new(width, height),
+            parallel: true,
+            activity: ChunkActivity::new(width, height),
+            temps: Vec::new(),
+            charges: Vec::new(),
+            supported: Vec::new(),
+            stack: Vec::new(),
+            pressure: PressureScratch::default(),
+        }
+    }
+
+    /// Swaps in new definitions, remapping existing cells by element name.
+    /// Cells whose element no longer exists become Air.
+    pub fn reload(&mut self, elements: Elements, reactions: Reactions) {
+        let remap: Vec<Option<ElementId>> = self
+            .elements
+            .iter()
+            .map(|(_, def)| elements.id(&def.name))
+            .collect();
+
+        for cell in self.cells.r.iter_mut() {
+            match remap[cell.id.0 as usize] {
+                Some(id) => cell.id = id,
+                None => *cell = elements.create(ElementId::AIR, &mut self.rng),
+            }
+            // only pillar-like elements are held in place
+            if elements[cell.id].pillar_like.is_none() {
+                cell.flags.remove(CellFlags::HELD);
+            }
+        }
+
+        self.elements = elements;
+        self.reactions = reactions;
+        self.activity.wake_all();
+    }
+
+    /// Wakes the cell at `(x, y)` and its neighbours. Call this after
+    /// editing `cells` directly: ticks only look at awake cells, so an
+    /// edit anywhere else is lost.
+    pub fn wake(&mut self, x: usize, y: usize) {
+        self.activity.wake_cell(x, y);
+    }
+
+    /// The cells the last tick updated, one rectangle per chunk that was
+    /// awake.
+    pub fn active_chunks(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.activity.active()
+    }
+
+    /// The cells that may look different after the last tick, one
+    /// rectangle per chunk. Redrawing these after every tick keeps a
+    /// picture of the world up to date, as long as cells edited directly
+    /// are woken with [`World::wake`].
+    pub fn changed_chunks(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.activity.changed()
+    }
+
+    /// The cells as they were right before the last tick.
+    pub fn previous_cells(&self) -> &[Cell] {
+        &self.cells.w
+    }
+
+    /// Creates a cell of the given element using the world's RNG.
+    pub fn create(&mut self, id: ElementId) -> Cell {
+        self.elements.create(id, &mut self.rng)
+    }
+
+    /// Advances the world by one tick. Passes that only look at cells near
+    /// the one they update run chunk by chunk, see [`update_chunks`]; each
+    /// draws its seed from the world's generator, so results don't depend
+    /// on threading. They skip cells where nothing has changed lately and
+    /// nothing is about to, so a settled world costs next to nothing.
+    pub fn update(&mut self, _delta: f32) {
+        let (w, h) = (self.width, self.height);
+        // everywhere else the cells haven't changed since the last copy
+        for rect in self.activity.changed() {
+            for y in rect.y0..rect.y1 {
+                let row = y * w + rect.x0..y * w + rect.x1;
+                self.cells.w[row.clone()].copy_from_slice(&self.cells.r[row]);
+            }
+        }
+
+        let ew = &mut self.cells.w;
+        let rng = &mut self.rng;
+        let elems = &self.elements;
+        let activity = &mut self.activity;
+        let parallel = self.parallel;
+        activity.advance();
+
+        // burning goes first so flames touch what they were placed against
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_combustion(elems, rng, ew, w, h)
+        });
+        update_support(
+            elems,
+            rng,
+            ew,
+            activity,
+            &mut self.supported,
+            &mut self.stack,
+            w,
+            h,
+        );
+        // the motion passes mark what they moved so it only moves once
+        update_chunks(ew, activity, 0, parallel, |ew, _| {
+            for i in ew.indices() {
+                ew[i].flags.remove(CellFlags::MOVED);
+            }
+        });
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_main(elems, rng, ew, w, h)
+        });
+        update_pressure(elems, rng, ew, activity, &mut self.pressure, w, h);
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_gases(elems, rng, ew, w, h)
+        });
+        self.wind.update(elems, ew, activity, w);
+        let wind = &self.wind;
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_gas_flow(elems, rng, ew, wind, w, h)
+        });
+        let reactions = &self.reactions;
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_chemistry(elems, reactions, rng, ew, w, h)
+        });
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_moisture(elems, rng, ew, w, h)
+        });
+
+        snapshot(&mut self.charges, ew, activity, w, h, |cell| cell.charge);
+        let charges = &self.charges;
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_electricity(elems, rng, ew, charges, w, h)
+        });
+
+        snapshot(&mut self.temps, ew, activity, w, h, |cell| cell.temperature);
+        let (temps, boundary) = (&self.temps, self.boundary);
+        update_chunks(ew, activity, 0, parallel, |ew, _| {
+            update_heat(elems, ew, temps, boundary, w, h)
+        });
+        update_chunks(ew, activity, rng.gen(), parallel, |ew, rng| {
+            update_phases(elems, rng, ew)
+        });
+
+        self.cells.swap();
+    }
+
+    pub fn render_to(&self, buf: &mut [u32]) {
+        for (out, cell) in buf.iter_mut().zip(&self.cells.r) {
+            *out = cell.render(self.color_of(cell));
+        }
+    }
+
+    /// Writes the RGBA colors of the cells in `rect` into `buf`, which
+    /// holds one color per cell of the whole grid, row by row.
+    pub fn render_rect_to(&self, rect: Rect, buf: &mut [[u8; 4]]) {
+        for y in rect.y0..rect.y1 {
+            let row = y * self.width + rect.x0..y * self.width + rect.x1;
+            for (out, cell) in buf[row.clone()].iter_mut().zip(&self.cells.r[row]) {
+                *out = cell.render_rgba(self.color_of(cell));
+            }
+        }
+    }
+
+    /// The color a cell is drawn with, its glow while it's charged.
+    pub(crate) fn color_of(&self, cell: &Cell) -> &ElementColor {
+        let def = &self.elements[cell.id];
+        match &def.glow {
+            Some(glow) if cell.charge > 0 => glow,
+            _ => &def.color,
+        }
+    }
+}
+
+/// Copies a value out of every cell the chunk passes update this tick and
+/// their neighbours, which they may read too, into `buf`, indexed like the
+/// grid. The rest of `buf` is left as it was.
+fn snapshot<T: Copy + Default + Send>(
+    buf: &mut Vec<T>,
+    cells: &[Cell],
+    activity: &ChunkActivity,
+    w: usize,
+    h: usize,
+    value: impl Fn(&Cell) -> T + Sync,
+) {
+    buf.resize(cells.len(), T::default());
+    let rects: Vec<Rect> = activity.active().map(|rect| rect.grown(w, h)).collect();
+    if rects.is_empty() {
+        return;
+    }
+    buf.par_chunks_mut(w).enumerate().for_each(|(y, row)| {
+        for rect in rects.iter().filter(|rect| (rect.y0..rect.y1).contains(&y)) {
+            let cells = &cells[y * w + rect.x0..y * w + rect.x1];
+            for (out, cell) in row[rect.x0..rect.x1].iter_mut().zip(cells) {
+                *out = value(cell);
+            }
+        }
+    });
+}
+
+fn update_chemistry(
+    elems: &Elements,
+    reactions: &Reactions,
+    rng: &mut SimRng,
+    ew: &mut Chunk,
+    w: usize,
+    h: usize,
+) {
+    let (xs, ys) = (ew.xs(), ew.ys());
+    let (startx, endx, step) = if rng.gen::<bool>() {
+        (xs.start as i32, xs.end as i32, 1)
+    } else {
+        (xs.end as i32 - 1, xs.start as i32 - 1, -1)
+    };
+
+    let mut ix = startx;
+    while ix != endx {
+        let x = ix as usize;
+
+        let mut y = ys.start;
+        while y < ys.end {
+            let cell_index = y * w + x;
+
+            // each touching pair gets one chance per tick, from its left or
+            // upper cell, which any pair that may react keeps awake
+            let directions = [(1, 0), (0, 1)];
+            for &(dx, dy) in &directions {
+                let new_x = x + dx;
+                let new_y = y + dy;
+
+                if !in_bounds(new_x as isize, new_y as isize, w, h) {
+                    continue;
+                }
+                let other_cell_index = new_y * w + new_x;
+                let (a, b) = (ew[cell_index], ew[other_cell_index]);
+
+                let rules = reactions.get(a.id, b.id);
+                if rules.is_empty() {
+                    continue;
+                }
+                let reaction = rules.iter().find(|reaction| {
+                    reaction.temperature_ok((a.temperature + b.temperature) / 2.0)
+                        && reaction.catalyst.is_none_or(|catalyst| {
+                            touches(ew, cell_index, catalyst, w, h)
+                                || touches(ew, other_cell_index, catalyst, w, h)
+                        })
+                        && rng.gen::<f32>() < reaction.probability
+                });
+
+                if let Some(reaction) = reaction {
+                    let (first, second) = if reaction.reactants.0 == a.id {
+                        (cell_index, other_cell_index)
+                    } else {
+                        (other_cell_index, cell_index)
+                    };
+                    for (index, product) in
+                        [(first, reaction.products.0), (second, reaction.products.1)]
+                    {
+                        if ew[index].id != product {
+                            ew[index] = elems.create(product, rng);
+                        }
+                    }
+                    ew.wake(cell_index);
+                    break;
+                }
+                // the pair may react on a later tick, or once it's warmer
+                ew.wake(cell_index);
+            }
+
+            y += 1;
+        }
+
+        ix += step;
+    }
+}
+
+fn update_main(elems: &Elements, rng: &mut SimRng, ew: &mut Chunk, w: usize, h: usize) {
+    let (xs, ys) = (ew.xs(), ew.ys());
+    let (startx, endx, step) = if rng.gen::<bool>() {
+        (xs.start as i32, xs.end as i32, 1)
+    } else {
+        (xs.end as i32 - 1, xs.start as i32 - 1, -1)
+    };
+    let mut ix = startx;
+
+    while ix != endx {
+        let x = ix as usize;
+
+        let mut repeat_once = false;
+        let mut y = ys.start;
+        while y < ys.end {
+            let cell_index = y * w + x;
+            let cell = &elems[ew[cell_index].id];
+            if cell.has(Attributes::CAN_FALL) && !ew[cell_index].held() && !ew[cell_index].moved() {
+                let end = move_ballistic(elems, rng, ew, x, y, w, h);
+                if end != cell_index {
+                    ew[end].flags.insert(CellFlags::MOVED);
+                    if repeat_once {
+                        repeat_once = false;
+                        y += 1;
+                    } else if elems[ew[end].id].has(Attributes::CAN_FALL) {
+                        repeat_once = true;
+                    }
+                    continue;
+                }
+
+                // wet cells stick together instead of sliding off diagonally
+                let slides = ew[cell_index].moisture < WET;
+                let positions_to_check: &[(isize, isize)] = if slides {
+                    if rng.gen() {
+                        &[(0, -1), (-1, -1), (1, -1)]
+                    } else {
+                        &[(0, -1), (1, -1), (-1, -1)]
+                    }
+                } else {
+                    &[(0, -1)]
+                };
+
+                if let Some((dx, dy)) = positions_to_check.iter().find(|&&(dx, dy)| {
+                    let new_x = (x as isize + dx) as usize;
+                    let new_y = (y as isize + dy) as usize;
+
+                    if !in_bounds(new_x as isize, new_y as isize, w, h) {
+                        return false;
+                    }
+
+                    let other_cell = &elems[ew[new_y * w + new_x].id];
+                    let current_cell_immovable = immovable(elems, &ew[(y - 1) * w + x]);
+                    let other_cell_immovable = immovable(elems, &ew[new_y * w + new_x]);
+                    let both_cells_solid =
+                        cell.has(Attributes::SOLID) && other_cell.has(Attributes::SOLID);
+                    let liquid_and_gas =
+                        cell.has(Attributes::LIQUID) && other_cell.has(Attributes::GAS);
+
+                    !current_cell_immovable
+                        && !other_cell_immovable
+                        && !liquid_and_gas
+                        && (!both_cells_solid && other_cell.density < cell.density)
+                }) {
+                    let new_x = x as isize + dx;
+                    let new_y = y as isize + dy;
+                    let new_index = new_y as usize * w + new_x as usize;
+                    ew.swap(cell_index, new_index);
+                    ew[new_index]
+                        .flags
+                        .insert(CellFlags::MOVED | CellFlags::FALLING);
+                    if repeat_once {
+                        repeat_once = false;
+                        y += 1;
+                    } else if elems[ew[new_index].id].has(Attributes::CAN_FALL) {
+                        repeat_once = true;
+                    }
+                    continue;
+                }
+                if !cell.has(Attributes::LIQUID) {
+                    y += 1;
+                    continue;
+                }
+
+                if let Some(end) = flow_sideways(elems, rng, ew, x, y, w, h) {
+                    ew[end].flags.insert(CellFlags::MOVED);
+                    y += 1;
+                    continue;
+                }
+            }
+
+            repeat_once = false;
+            y += 1;
+        }
+
+        y = ys.start;
+        while y < ys.end {
+            let cell = &mut ew[y * w + x];
+            let def = &elems[cell.id];
+
+            if def.has(Attributes::SPARKLE) {
+                cell.vary_color(&def.color, rng);
+            }
+
+            y += 1;
+        }
+        ix += step;
+    }
+}
+
+fn update_gases(elems: &Elements, rng: &mut SimRng, ew: &mut Chunk, w: usize, h: usize) {
+    let (xs, ys) = (ew.xs(), ew.ys());
+    let (startx, endx, step) = if rng.gen::<bool>() {
+        (xs.start as i32, xs.end as i32, 1)
+    } else {
+        (xs.end as i32 - 1, xs.start as i32 - 1, -1)
+    };
+    let mut ix = startx;
+
+    while ix != endx {
+        let x = ix as usize;
+
+        let mut repeat_once = false;
+        let mut y = ys.end - 1;
+        loop {
+            let cell_index = y * w + x;
+            let cell = &elems[ew[cell_index].id];
+            if cell.has(Attributes::GAS) && !ew[cell_index].moved() {
+                let up = if rng.gen() {
+                    [(0, 1), (-1, 1), (1, 1)]
+                } else {
+                    [(0, 1), (1, 1), (-1, 1)]
+                };
+                let sideways = if rng.gen() {
+                    [(-1, 0), (1, 0)]
+                } else {
+                    [(1, 0), (-1, 0)]
+                };
+
+                if let Some((dx, dy)) = up.into_iter().chain(sideways).find(|&(dx, dy)| {
+                    let new_x = (x as isize + dx) as usize;
+                    let new_y = (y as isize + dy) as usize;
+
+                    if !in_bounds(new_x as isize, new_y as isize, w, h) {
+                        return false;
+                    }
+
+                    let other_cell = &elems[ew[new_y * w + new_x].id];
+                    let current_cell_immovable =
+                        new_y == y + 1 && immovable(elems, &ew[(y + 1) * w + x]);
+                    let other_cell_immovable = immovable(elems, &ew[new_y * w + new_x]);
+
+                    let other_cell_fluid = other_cell.has(Attributes::GAS)
+                        || other_cell.has(Attributes::LIQUID)
+                        || other_cell.has(Attributes::AIR);
+
+                    !current_cell_immovable
+                        && !other_cell_immovable
+                        && (other_cell_fluid && other_cell.density > cell.density)
+                }) {
+                    let new_x = x as isize + dx;
+                    let new_y = y as isize + dy;
+                    let new_index = new_y as usize * w + new_x as usize;
+                    ew.swap(cell_index, new_index);
+                    ew[new_index].flags.insert(CellFlags::MOVED);
+
+                    if repeat_once {
+                        repeat_once = false;
+                        if y == ys.start {
+                            break;
+                        }
+                        y -= 1;
+                    } else if elems[ew[new_index].id].has(Attributes::GAS) {
+                        repeat_once = true;
+                    }
+                    continue;
+                }
+            }
+
+            repeat_once = false;
+            if y > ys.start {
+                y -= 1;
+            } else {
+                break;
+            }
+        }
+        ix += step;
+    }
+}
+
+/// Whether nothing can push the cell out of its place.
+pub(crate) fn immovable(elems: &Elements, cell: &Cell) -> bool {
+    elems[cell.id].has(Attributes::IMMOVABLE) || cell.held()
+}
+
+/// Whether any 4-neighbour of the cell at `index` is of the given element.
+pub(crate) fn touches(ew: &Chunk, index: usize, element: ElementId, w: usize, h: usize) -> bool {
+    let (x, y) = ((index % w) as isize, (index / w) as isize);
+    [(-1, 0), (1, 0), (0, -1), (0, 1)].iter().any(|&(dx, dy)| {
+        in_bounds(x + dx, y + dy, w, h)
+            && ew[(y + dy) as usize * w + (x + dx) as usize].id == element
+    })
+}
+
+pub(crate) fn in_bounds(x: isize, y: isize, w: usize, h: usize) -> bool {
+    x >= 0 && x < w as isize && y >= 0 && y < h as isize
+}
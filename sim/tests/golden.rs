@@ -0,0 +1,17 @@
+//! Runs every scene and compares it with its golden image, like
+//! `pixelgame-headless golden`, which also rewrites them after a deliberate
+//! change with `--bless`.
+
+use pixelgame_sim::scenes::SCENES;
+
+#[test]
+fn scenes_match_golden_images() {
+    let failures: Vec<_> = SCENES
+        .iter()
+        .filter_map(|scene| {
+            let problem = scene.check_golden().err()?;
+            Some(format!("{}: {problem}", scene.name))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
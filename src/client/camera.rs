@@ -1,6 +1,6 @@
 use winit::dpi::PhysicalSize;
 
-use crate::util::point::Point;
+use pixelgame_sim::util::point::Point;
 
 const DEFAULT_ASPECT_RATIO: f32 = 16. / 9.;
 const DEFAULT_SCALE: f32 = 0.02;
@@ -30,7 +30,7 @@ impl Camera {
         };
         let mut pos = cursor_pos / p_size * 2.0 - Point { x: 1.0, y: 1.0 };
         pos.y = -pos.y;
-        pos = pos / self.proj_for(size);
+        pos /= self.proj_for(size);
         pos += self.pos;
         pos
     }
@@ -0,0 +1,73 @@
+use std::{
+    fs, io,
+    time::{Instant, SystemTime},
+};
+
+use pixelgame_sim::{
+    data::{parse_elements, parse_reactions, DEFAULT_ELEMENTS, DEFAULT_REACTIONS},
+    elements::Elements,
+    reactions::Reactions,
+    world::World,
+};
+
+use super::rsc::{ELEMENTS_PATH, REACTIONS_PATH, RELOAD_INTERVAL};
+
+/// Loads element and reaction definitions from their data files, using the
+/// built-in definitions for any file that doesn't exist.
+pub fn load_defs() -> Result<(Elements, Reactions), String> {
+    let elements = parse_elements(&read(ELEMENTS_PATH, DEFAULT_ELEMENTS)?)
+        .map_err(|err| format!("{ELEMENTS_PATH}: {err}"))?;
+    let reactions = parse_reactions(&read(REACTIONS_PATH, DEFAULT_REACTIONS)?, &elements)
+        .map_err(|err| format!("{REACTIONS_PATH}: {err}"))?;
+    Ok((elements, reactions))
+}
+
+fn read(path: &str, default: &str) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(src) => Ok(src),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(default.to_string()),
+        Err(err) => Err(format!("{path}: {err}")),
+    }
+}
+
+/// Reloads the definition files into the world whenever they change on disk.
+pub struct DefsWatcher {
+    modified: [Option<SystemTime>; 2],
+    next_check: Instant,
+}
+
+impl DefsWatcher {
+    pub fn new() -> Self {
+        Self {
+            modified: modified_times(),
+            next_check: Instant::now() + RELOAD_INTERVAL,
+        }
+    }
+
+    pub fn update(&mut self, world: &mut World) {
+        let now = Instant::now();
+        if now < self.next_check {
+            return;
+        }
+        self.next_check = now + RELOAD_INTERVAL;
+
+        let modified = modified_times();
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        // a failed reload keeps the old tables, so a half-saved file is harmless
+        match load_defs() {
+            Ok((elements, reactions)) => {
+                world.reload(elements, reactions);
+                println!("Reloaded element and reaction definitions");
+            }
+            Err(err) => eprintln!("Keeping previous definitions: {err}"),
+        }
+    }
+}
+
+fn modified_times() -> [Option<SystemTime>; 2] {
+    [ELEMENTS_PATH, REACTIONS_PATH].map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+}
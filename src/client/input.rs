@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use winit::event::{ElementState, MouseScrollDelta, VirtualKeyCode, WindowEvent, MouseButton};
 
-use crate::util::point::Point;
+use pixelgame_sim::util::point::Point;
 
 pub struct Input {
     pub mouse_pixel_pos: Point<f32>,
@@ -1,14 +1,11 @@
 mod camera;
+mod defs;
 mod input;
 mod render;
 mod rsc;
 mod run;
 mod state;
 mod update;
-mod world;
-mod swap_buffer;
-mod elements;
-mod reactions;
 
 pub use state::*;
 pub use run::*;
@@ -1,5 +1,5 @@
-mod instance;
 mod renderer;
+mod texture;
 pub mod tile;
 mod uniform;
 
@@ -1,5 +1,6 @@
-use super::tile::TilePipeline;
+use super::tile::{LegendView, TilePipeline, TileView};
 use crate::client::{rsc::CLEAR_COLOR, ClientState};
+use pixelgame_sim::chunks::Rect;
 use winit::{
     dpi::PhysicalSize,
     event_loop::EventLoop,
@@ -12,17 +13,21 @@ pub struct Renderer {
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
+    #[allow(dead_code)]
     adapter: wgpu::Adapter,
     encoder: Option<wgpu::CommandEncoder>,
     staging_belt: wgpu::util::StagingBelt,
-    tile_pipeline: TilePipeline,
+    tile_pipeline: TilePipeline<TileView>,
+    /// Draws the legend of the debug view over the grid.
+    legend_pipeline: TilePipeline<LegendView>,
+    show_legend: bool,
 }
 
 impl Renderer {
     pub async fn new(event_loop: &EventLoop<()>, fullscreen: bool) -> Self {
         let window = WindowBuilder::new()
             .with_visible(false)
-            .build(&event_loop)
+            .build(event_loop)
             .unwrap();
 
         if fullscreen {
@@ -94,7 +99,9 @@ impl Renderer {
         let staging_belt = wgpu::util::StagingBelt::new(4096 * 4);
 
         Self {
-            tile_pipeline: TilePipeline::new(&device, &config.format),
+            tile_pipeline: TilePipeline::new(&device, &config.format, "Tile"),
+            legend_pipeline: TilePipeline::new(&device, &config.format, "Legend"),
+            show_legend: false,
             window,
             encoder: None,
             staging_belt,
@@ -135,6 +142,9 @@ impl Renderer {
                 occlusion_query_set: None,
             });
             self.tile_pipeline.draw(render_pass);
+            if self.show_legend {
+                self.legend_pipeline.draw(render_pass);
+            }
         }
 
         self.staging_belt.finish();
@@ -143,21 +153,46 @@ impl Renderer {
         self.staging_belt.recall();
     }
 
-    pub fn update<'a>(&mut self, state: &ClientState) {
+    pub fn update(&mut self, state: &ClientState) {
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Render Encoder"),
             });
+        let update_data = RenderUpdateData {
+            state,
+            size: &self.window.inner_size(),
+        };
         self.tile_pipeline.update(
             &self.device,
             &mut encoder,
             &mut self.staging_belt,
-            &RenderUpdateData {
-                state,
-                size: &self.window.inner_size(),
-            },
+            &update_data,
+            &state.grid,
+            state.world.width,
+            state.world.height,
+            &state.damage,
         );
+        self.show_legend = state.legend_image.is_some();
+        if let Some(image) = &state.legend_image {
+            let whole = [Rect {
+                x0: 0,
+                y0: 0,
+                x1: image.width,
+                y1: image.height,
+            }];
+            let damage: &[Rect] = if state.legend_dirty { &whole } else { &[] };
+            self.legend_pipeline.update(
+                &self.device,
+                &mut encoder,
+                &mut self.staging_belt,
+                &update_data,
+                &image.pixels,
+                image.width,
+                image.height,
+                damage,
+            );
+        }
         self.encoder = Some(encoder);
     }
 
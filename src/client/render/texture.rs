@@ -0,0 +1,201 @@
+use pixelgame_sim::chunks::Rect;
+
+/// An RGBA8 texture holding one texel per cell of the grid, sampled with
+/// nearest filtering so every cell stays a sharp square.
+pub struct GridTexture {
+    width: u32,
+    height: u32,
+    texture: wgpu::Texture,
+    /// Damaged parts of the grid are staged here, each with rows padded to
+    /// the alignment texture copies need, then copied into the texture.
+    upload: wgpu::Buffer,
+    view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+    label: String,
+}
+
+impl GridTexture {
+    pub fn init(device: &wgpu::Device, label: &str) -> Self {
+        let texture = Self::init_texture(device, label, 1, 1);
+        Self {
+            width: 1,
+            height: 1,
+            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            texture,
+            upload: Self::init_upload(device, label, 0),
+            sampler: device.create_sampler(&wgpu::SamplerDescriptor {
+                label: Some(&(label.to_owned() + " Sampler")),
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                mag_filter: wgpu::FilterMode::Nearest,
+                min_filter: wgpu::FilterMode::Nearest,
+                ..Default::default()
+            }),
+            label: label.to_string(),
+        }
+    }
+
+    /// Uploads the cells of `data` inside the `damage` rectangles through
+    /// the staging belt, nothing if there are none. If the grid size
+    /// changed the texture is recreated and filled completely, and `true`
+    /// is returned so bind groups using it can be rebuilt.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        belt: &mut wgpu::util::StagingBelt,
+        data: &[[u8; 4]],
+        width: u32,
+        height: u32,
+        damage: &[Rect],
+    ) -> bool {
+        let resized = (width, height) != (self.width, self.height);
+        let whole = [Rect {
+            x0: 0,
+            y0: 0,
+            x1: width as usize,
+            y1: height as usize,
+        }];
+        let damage = if resized {
+            (self.width, self.height) = (width, height);
+            self.texture = Self::init_texture(device, &self.label, width, height);
+            self.view = self
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            &whole[..]
+        } else {
+            damage
+        };
+
+        let size = damage.iter().map(Self::upload_size).sum::<u64>();
+        if size == 0 {
+            return resized;
+        }
+        if size > self.upload.size() {
+            self.upload = Self::init_upload(device, &self.label, size);
+        }
+
+        let mut offset = 0;
+        for rect in damage {
+            let bytes_per_row = Self::padded_row_size(rect);
+            let Some(len) = std::num::NonZeroU64::new(Self::upload_size(rect)) else {
+                continue;
+            };
+            let mut view = belt.write_buffer(encoder, &self.upload, offset, len, device);
+            for (y, row) in (rect.y0..rect.y1).zip(view.chunks_exact_mut(bytes_per_row as usize)) {
+                let start = y * width as usize;
+                let cells = &data[start + rect.x0..start + rect.x1];
+                row[..cells.len() * 4].copy_from_slice(bytemuck::cast_slice(cells));
+            }
+            drop(view);
+
+            encoder.copy_buffer_to_texture(
+                wgpu::ImageCopyBuffer {
+                    buffer: &self.upload,
+                    layout: wgpu::ImageDataLayout {
+                        offset,
+                        bytes_per_row: Some(bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                wgpu::ImageCopyTexture {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: rect.x0 as u32,
+                        y: rect.y0 as u32,
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::Extent3d {
+                    width: (rect.x1 - rect.x0) as u32,
+                    height: (rect.y1 - rect.y0) as u32,
+                    depth_or_array_layers: 1,
+                },
+            );
+            offset += len.get();
+        }
+        resized
+    }
+
+    /// Bytes per staged row of `rect`, padded so every row starts aligned.
+    fn padded_row_size(rect: &Rect) -> u32 {
+        let bytes = (rect.x1 - rect.x0) as u32 * 4;
+        bytes.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+    }
+
+    fn upload_size(rect: &Rect) -> u64 {
+        Self::padded_row_size(rect) as u64 * (rect.y1 - rect.y0) as u64
+    }
+
+    fn init_upload(device: &wgpu::Device, label: &str, size: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&(label.to_owned() + " Upload Buffer")),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            size,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn init_texture(device: &wgpu::Device, label: &str, width: u32, height: u32) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(&(label.to_owned() + " Texture")),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            // colors are passed through as they are, like the old per-cell
+            // vertex colors were
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        })
+    }
+
+    pub fn layout_entries(
+        texture_binding: u32,
+        sampler_binding: u32,
+    ) -> [wgpu::BindGroupLayoutEntry; 2] {
+        [
+            wgpu::BindGroupLayoutEntry {
+                binding: texture_binding,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: sampler_binding,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ]
+    }
+
+    pub fn bind_group_entries(
+        &self,
+        texture_binding: u32,
+        sampler_binding: u32,
+    ) -> [wgpu::BindGroupEntry<'_>; 2] {
+        [
+            wgpu::BindGroupEntry {
+                binding: texture_binding,
+                resource: wgpu::BindingResource::TextureView(&self.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: sampler_binding,
+                resource: wgpu::BindingResource::Sampler(&self.sampler),
+            },
+        ]
+    }
+}
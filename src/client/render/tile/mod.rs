@@ -1,6 +1,5 @@
-mod instance;
 mod view;
 mod pipeline;
 
 pub use pipeline::*;
-pub use instance::*;
+pub use view::{LegendView, TileView};
@@ -1,65 +1,71 @@
-use super::{instance::TileInstance, view::TileView};
-use crate::client::render::{instance::Instances, uniform::Uniform, RenderUpdateData};
+use pixelgame_sim::chunks::Rect;
 
-pub struct TilePipeline {
+use crate::client::render::{
+    texture::GridTexture,
+    uniform::{Uniform, UniformData},
+    RenderUpdateData,
+};
+
+/// Draws a grid of colors as one textured quad, with a texel per color.
+/// Where the quad goes is up to the view uniform `V`: the grid of cells
+/// covers the world, the legend a corner of the window.
+pub struct TilePipeline<V: bytemuck::Pod + PartialEq + UniformData> {
     pipeline: wgpu::RenderPipeline,
-    view: Uniform<TileView>,
-    instances: Instances<TileInstance>,
+    view: Uniform<V>,
+    grid: GridTexture,
+    bind_group_layout: wgpu::BindGroupLayout,
     bind_group: wgpu::BindGroup,
+    label: String,
 }
 
-impl TilePipeline {
-    pub fn new(device: &wgpu::Device, format: &wgpu::TextureFormat) -> Self {
+impl<V: Default + bytemuck::Pod + PartialEq + UniformData> TilePipeline<V> {
+    pub fn new(device: &wgpu::Device, format: &wgpu::TextureFormat, label: &str) -> Self {
         // shaders
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Tile Shader"),
+            label: Some(&format!("{label} Shader")),
             source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
         });
 
-        let view = Uniform::<TileView>::init(device, "Tile View Uniform", 0);
-        let instances = Instances::<TileInstance>::init(
-            device,
-            "Tile Instance",
-            0,
-            wgpu::VertexFormat::Float32x4,
-        );
+        let view = Uniform::<V>::init(device, &format!("{label} View Uniform"), 0);
+        let grid = GridTexture::init(device, &format!("{label} Grid"));
 
         // bind groups
+        let [texture_entry, sampler_entry] = GridTexture::layout_entries(1, 2);
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
-            label: Some("tile_bind_group_layout"),
+                texture_entry,
+                sampler_entry,
+            ],
+            label: Some(&format!("{label} Bind Group Layout")),
         });
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &bind_group_layout,
-            entries: &[view.bind_group_entry()],
-            label: Some("tile_bind_group"),
-        });
+        let bind_group = Self::init_bind_group(device, &bind_group_layout, &view, &grid, label);
 
         // pipeline
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Tile Pipeline Layout"),
+                label: Some(&format!("{label} Pipeline Layout")),
                 bind_group_layouts: &[&bind_group_layout],
                 push_constant_ranges: &[],
             });
 
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Tile Pipeline"),
+            label: Some(&format!("{label} Pipeline")),
             layout: Some(&render_pipeline_layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: "vs_main",
-                buffers: &[instances.desc()],
+                buffers: &[],
             },
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
@@ -91,27 +97,66 @@ impl TilePipeline {
         Self {
             pipeline: render_pipeline,
             view,
-            instances,
+            grid,
+            bind_group_layout,
             bind_group,
+            label: label.to_string(),
         }
     }
 
-    pub fn update<'a>(
+    fn init_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        view: &Uniform<V>,
+        grid: &GridTexture,
+        label: &str,
+    ) -> wgpu::BindGroup {
+        let [texture_entry, sampler_entry] = grid.bind_group_entries(1, 2);
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[view.bind_group_entry(), texture_entry, sampler_entry],
+            label: Some(&format!("{label} Bind Group")),
+        })
+    }
+
+    /// Uploads the `damage`d parts of `colors`, a `width`×`height` grid
+    /// stored row by row, and updates the view.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
         &mut self,
         device: &wgpu::Device,
         encoder: &mut wgpu::CommandEncoder,
         belt: &mut wgpu::util::StagingBelt,
         update_data: &RenderUpdateData,
+        colors: &[[u8; 4]],
+        width: usize,
+        height: usize,
+        damage: &[Rect],
     ) {
-        self.instances
-            .update(device, encoder, belt, &update_data.state.grid);
+        let resized = self.grid.update(
+            device,
+            encoder,
+            belt,
+            colors,
+            width as u32,
+            height as u32,
+            damage,
+        );
+        if resized {
+            self.bind_group = Self::init_bind_group(
+                device,
+                &self.bind_group_layout,
+                &self.view,
+                &self.grid,
+                &self.label,
+            );
+        }
         self.view.update(device, encoder, belt, update_data);
     }
 
     pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
-        self.instances.set_in(render_pass);
-        render_pass.draw(0..4, 0..self.instances.len() as u32);
+        render_pass.draw(0..4, 0..1);
     }
 }
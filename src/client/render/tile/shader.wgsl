@@ -1,38 +1,36 @@
 // Vertex shader
 
 struct VertexOutput {
-    @location(0) rgba: vec4<f32>,
+    @location(0) uv: vec2<f32>,
     @builtin(position) clip_position: vec4<f32>,
 };
 
-struct InstanceInput {
-    @location(0) rgba: vec4<f32>,
-};
-
 struct ViewUniform {
     pos: vec2<f32>,
     proj: vec2<f32>,
-    width: u32,
+    size: vec2<f32>,
 };
 
 @group(0) @binding(0)
 var<uniform> view: ViewUniform;
+@group(0) @binding(1)
+var grid: texture_2d<f32>;
+@group(0) @binding(2)
+var grid_sampler: sampler;
 
 @vertex
 fn vs_main(
     @builtin(vertex_index) vi: u32,
-    @builtin(instance_index) i: u32,
-    in: InstanceInput,
 ) -> VertexOutput {
     var out: VertexOutput;
 
-    var pos = vec2<f32>(f32(vi % 2u), f32(vi / 2u));
-    pos.x += f32(i % view.width);
-    pos.y += f32(i / view.width);
+    // one quad over the whole world, row 0 of the texture at the bottom
+    let uv = vec2<f32>(f32(vi % 2u), f32(vi / 2u));
+    var pos = uv * view.size;
     pos += view.pos;
     pos *= view.proj;
     out.clip_position = vec4<f32>(pos.x, pos.y, 0.0, 1.0);
-    out.rgba = in.rgba;
+    out.uv = uv;
 
     return out;
 }
@@ -43,5 +41,5 @@ fn vs_main(
 fn fs_main(
     in: VertexOutput
 ) -> @location(0) vec4<f32> {
-    return in.rgba;
+    return textureSample(grid, grid_sampler, in.uv);
 }
@@ -1,4 +1,8 @@
-use crate::{util::point::Point, client::render::{uniform::UniformData, RenderUpdateData}};
+use crate::client::{
+    render::{uniform::UniformData, RenderUpdateData},
+    rsc::{LEGEND_MARGIN, LEGEND_SCALE},
+};
+use pixelgame_sim::util::point::Point;
 
 #[repr(C)]
 #[repr(align(16))]
@@ -6,7 +10,8 @@ use crate::{util::point::Point, client::render::{uniform::UniformData, RenderUpd
 pub struct TileView {
     pub pos: Point<f32>,
     pub proj: Point<f32>,
-    pub width: u32,
+    /// World size in cells.
+    pub size: Point<f32>,
 }
 
 unsafe impl bytemuck::Pod for TileView {}
@@ -16,7 +21,10 @@ impl UniformData for TileView {
         let new = TileView {
             pos: -data.state.camera.pos,
             proj: data.state.camera.proj_for(data.size),
-            width: data.state.width
+            size: Point::new(
+                data.state.world.width as f32,
+                data.state.world.height as f32,
+            ),
         };
         if *self == new {
             false
@@ -29,7 +37,46 @@ impl UniformData for TileView {
 
 impl Default for TileView {
     fn default() -> Self {
-        Self { pos: Point::zero(), proj: Point::zero(), width: 0 }
+        Self { pos: Point::zero(), proj: Point::zero(), size: Point::zero() }
     }
 }
 
+/// Places the legend image in the top left corner of the window, each
+/// pixel of it `LEGEND_SCALE` pixels wide, with its top row at the top.
+#[repr(C)]
+#[repr(align(16))]
+#[derive(Clone, Copy, Default, PartialEq, bytemuck::Zeroable)]
+pub struct LegendView {
+    pub pos: Point<f32>,
+    pub proj: Point<f32>,
+    /// Legend size in pixels of the image.
+    pub size: Point<f32>,
+}
+
+unsafe impl bytemuck::Pod for LegendView {}
+
+impl UniformData for LegendView {
+    fn update(&mut self, data: &RenderUpdateData) -> bool {
+        let Some(image) = &data.state.legend_image else {
+            return false;
+        };
+        let (width, height) = (data.size.width as f32, data.size.height as f32);
+        let scale = LEGEND_SCALE as f32;
+        // the shader computes (uv * size + pos) * proj, which flipped upside
+        // down and shifted by the margin lands in the corner
+        let new = LegendView {
+            pos: Point::new(
+                (2.0 * LEGEND_MARGIN - width) / (2.0 * scale),
+                (2.0 * LEGEND_MARGIN - height) / (2.0 * scale),
+            ),
+            proj: Point::new(2.0 * scale / width, -2.0 * scale / height),
+            size: Point::new(image.width as f32, image.height as f32),
+        };
+        if *self == new {
+            false
+        } else {
+            *self = new;
+            true
+        }
+    }
+}
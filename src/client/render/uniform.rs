@@ -28,11 +28,11 @@ impl<T: Default + PartialEq + bytemuck::Pod + UniformData> Uniform<T> {
 }
 
 impl<T: PartialEq + bytemuck::Pod + UniformData> Uniform<T> {
-    pub fn bind_group_entry(&self) -> wgpu::BindGroupEntry {
-        return wgpu::BindGroupEntry {
+    pub fn bind_group_entry(&self) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
             binding: self.binding,
             resource: self.buffer.as_entire_binding(),
-        };
+        }
     }
     pub fn update(
         &mut self,
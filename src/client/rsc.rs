@@ -3,6 +3,40 @@ use std::time::Duration;
 pub const FPS: u32 = 60;
 pub const FRAME_TIME: Duration = Duration::from_millis(1000 / FPS as u64);
 
+pub const WORLD_WIDTH: usize = 100;
+pub const WORLD_HEIGHT: usize = 100;
+
+/// Degrees added or removed per frame by the heat and cool keys.
+pub const HEAT_STEP: f32 = 50.0;
+
+/// Speed, in cells per tick, given to the cell under the cursor by the arrow keys.
+pub const FLING_SPEED: f32 = 4.0;
+
+/// Radius, in cells, of the area the blower pushes wind into.
+pub const BLOWER_RADIUS: f32 = 6.0;
+/// Wind added by the blower per cell the cursor moves.
+pub const BLOWER_STRENGTH: f32 = 0.1;
+
+/// Where the screenshot key saves PNG files, and how many pixels wide each
+/// cell is in them.
+pub const SCREENSHOT_DIR: &str = "screenshots";
+pub const SCREENSHOT_SCALE: usize = 4;
+
+/// Where the recording keys save GIFs and PNG sequences, which ticks they
+/// capture and how many pixels wide each cell is in them.
+pub const RECORDING_DIR: &str = "recordings";
+pub const RECORD_EVERY: usize = 2;
+pub const RECORD_SCALE: usize = 2;
+
+/// Pixels per font pixel of the debug view legend, and its distance in
+/// pixels from the top left corner of the window.
+pub const LEGEND_SCALE: usize = 3;
+pub const LEGEND_MARGIN: f32 = 8.0;
+
+pub const ELEMENTS_PATH: &str = "sim/assets/elements.ron";
+pub const REACTIONS_PATH: &str = "sim/assets/reactions.ron";
+pub const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
 pub const CLEAR_COLOR: wgpu::Color = wgpu::Color {
     r: 0.1,
     g: 0.1,
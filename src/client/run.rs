@@ -1,4 +1,12 @@
-use super::{input::Input, ClientState, render::Renderer, rsc::FRAME_TIME, update::update};
+use super::{
+    defs::load_defs,
+    input::Input,
+    render::Renderer,
+    rsc::{FRAME_TIME, WORLD_HEIGHT, WORLD_WIDTH},
+    update::update,
+    ClientState,
+};
+use pixelgame_sim::world::World;
 use std::time::Instant;
 use winit::{
     event::{Event, WindowEvent},
@@ -10,7 +18,17 @@ pub async fn run_client() {
     let mut renderer = Renderer::new(&event_loop, false).await;
     let frame_time = FRAME_TIME;
     let mut exit = false;
-    let mut state = ClientState::new();
+    let (width, height) = world_size();
+    let seed = std::env::args()
+        .nth(2)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or_else(rand_seed);
+    let (elements, reactions) = load_defs().unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(1);
+    });
+    let world = World::with_elements(width, height, seed, elements, reactions);
+    let mut state = ClientState::new(world);
 
     let mut target = Instant::now();
     let mut input = Input::new();
@@ -53,3 +71,22 @@ pub async fn run_client() {
         }
     });
 }
+
+/// Reads the world size from the first argument, e.g. `512x256`.
+/// An optional second argument fixes the simulation seed.
+fn world_size() -> (usize, usize) {
+    std::env::args()
+        .nth(1)
+        .and_then(|arg| {
+            let (w, h) = arg.split_once('x')?;
+            Some((w.parse().ok()?, h.parse().ok()?))
+        })
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .unwrap_or((WORLD_WIDTH, WORLD_HEIGHT))
+}
+
+fn rand_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |t| t.as_nanos() as u64)
+}
@@ -1,34 +1,71 @@
-use super::{camera::Camera, render::tile::TileInstance, world::World};
+use pixelgame_sim::{
+    chunks::Rect,
+    image::{Image, Overlays},
+    recording::Recorder,
+    util::point::Point,
+    view::{Legend, View},
+    world::World,
+};
+
+use super::{camera::Camera, defs::DefsWatcher};
 
 pub struct ClientState {
     pub camera: Camera,
     pub camera_scroll: f32,
-    pub mouse_mode: MouseMode,
-    pub grid: Vec<TileInstance>,
+    /// Cursor position in world space last frame, for the blower.
+    pub prev_cursor: Point<f32>,
+    /// Outlines the part of each chunk the simulation updated last tick.
+    pub show_active: bool,
+    /// The running recording, if any.
+    pub recording: Option<Recorder>,
+    /// What the colors of the cells show.
+    pub view: View,
+    /// RGBA color of every cell, as drawn.
+    pub grid: Vec<[u8; 4]>,
+    /// Parts of `grid` redrawn this frame, which need uploading.
+    pub damage: Vec<Rect>,
+    /// Parts of `grid` only right for this frame, redrawn next frame: the
+    /// outlines, and what the changed view marked as changed.
+    pub stale: Vec<Rect>,
+    /// The view's legend, and the image of it drawn in the corner, if the
+    /// view has one.
+    pub legend: Legend,
+    pub legend_image: Option<Image>,
+    /// Whether `legend_image` was redrawn this frame and needs uploading.
+    pub legend_dirty: bool,
     pub world: World,
-    pub width: u32,
+    pub defs: DefsWatcher,
 }
 
 impl ClientState {
-    pub fn new() -> Self {
-        let world = World::new();
+    pub fn new(world: World) -> Self {
+        println!("Seed: {}", world.seed);
+        let (width, height) = (world.width, world.height);
         Self {
             camera: Camera::default(),
             camera_scroll: 0.0,
-            mouse_mode: MouseMode::Dens,
-            grid: vec![TileInstance {
-                r: 0.0,
-                g: 0.0,
-                b: 0.0,
-                a: 0.0,
-            }; super::world::H*super::world::W],
-            width: super::world::W as u32,
+            prev_cursor: Point::zero(),
+            show_active: false,
+            recording: None,
+            view: View::Normal,
+            grid: vec![[0; 4]; width * height],
+            damage: Vec::new(),
+            stale: Vec::new(),
+            legend: Legend::default(),
+            legend_image: None,
+            legend_dirty: false,
             world,
+            defs: DefsWatcher::new(),
         }
     }
 }
 
-pub enum MouseMode {
-    Dens,
-    Vel,
+impl ClientState {
+    /// The overlays shown on screen, for screenshots and recordings.
+    pub fn overlays(&self) -> Overlays {
+        let mut overlays = Overlays::empty();
+        overlays.set(Overlays::ACTIVE_CHUNKS, self.show_active);
+        overlays.set(Overlays::LEGEND, self.legend_image.is_some());
+        overlays
+    }
 }
@@ -1,14 +1,26 @@
-use std::time::Duration;
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
 
-use crate::{
-    client::{world::{W}, elements::{DEF_ELEMS, ElementType}},
-    util::point::Point, get_element,
+use pixelgame_sim::{
+    chunks::Rect,
+    image::{Image, ImageError, ACTIVE_COLOR},
+    recording::{RecordOptions, Recorder},
+    util::point::Point,
+    view::View,
 };
+use winit::dpi::PhysicalSize;
 
 use super::{
     input::Input,
-    render::{tile::TileInstance, Renderer},
-    ClientState, MouseMode,
+    rsc::{
+        BLOWER_RADIUS, BLOWER_STRENGTH, FLING_SPEED, FPS, HEAT_STEP, LEGEND_SCALE, RECORDING_DIR,
+        RECORD_EVERY, RECORD_SCALE, SCREENSHOT_DIR, SCREENSHOT_SCALE,
+    },
+    render::Renderer,
+    ClientState,
 };
 use winit::event::VirtualKeyCode as Key;
 
@@ -21,9 +33,10 @@ pub fn update(
     let cursor_pos = state
         .camera
         .cursor_world_pos(input.mouse_pixel_pos, &renderer.window.inner_size());
+    let width = state.world.width;
     let cursor_grid_pos = cursor_pos.to_grid(Point {
-        x: W as u32,
-        y: W as u32,
+        x: width as u32,
+        y: state.world.height as u32,
     });
 
     if input.just_pressed(Key::Escape) {
@@ -50,30 +63,82 @@ pub fn update(
     }
 
     if input.just_pressed(Key::B) {
-        if let Some(pos) = cursor_grid_pos {
-            let i = pos.index(W as u32) as usize;
-            state.world.cells.r[i] = get_element!(ElementType::Bendium);
-        }
+        place(state, cursor_grid_pos, "Bendium");
     }
 
     if input.just_pressed(Key::V) {
-        if let Some(pos) = cursor_grid_pos {
-            let i = pos.index(W as u32) as usize;
-            state.world.cells.r[i] = get_element!(ElementType::Steam);
+        place(state, cursor_grid_pos, "Steam");
+    }
+
+    if input.just_pressed(Key::I) {
+        place(state, cursor_grid_pos, "Ice");
+    }
+
+    if input.just_pressed(Key::F) {
+        place(state, cursor_grid_pos, "Fire");
+    }
+
+    if input.pressed(Key::O) {
+        place(state, cursor_grid_pos, "Oil");
+    }
+
+    if input.pressed(Key::P) {
+        place(state, cursor_grid_pos, "Wood");
+    }
+
+    if input.pressed(Key::K) {
+        place(state, cursor_grid_pos, "Coal");
+    }
+
+    if input.pressed(Key::Q) {
+        place(state, cursor_grid_pos, "Water");
+    }
+
+    if input.pressed(Key::Y) {
+        place(state, cursor_grid_pos, "Honey");
+    }
+
+    if input.pressed(Key::J) {
+        place(state, cursor_grid_pos, "Lava");
+    }
+
+    if input.pressed(Key::M) {
+        place(state, cursor_grid_pos, "Metal");
+    }
+
+    if input.just_pressed(Key::N) {
+        place(state, cursor_grid_pos, "Battery");
+    }
+
+    if input.just_pressed(Key::E) {
+        place(state, cursor_grid_pos, "Spark");
+    }
+
+    if input.just_pressed(Key::L) {
+        place(state, cursor_grid_pos, "Lamp");
+    }
+
+    if input.pressed(Key::R) {
+        place(state, cursor_grid_pos, "Robustium");
+    }
+
+    if let Some(pos) = cursor_grid_pos {
+        let i = pos.index(width as u32) as usize;
+        if input.pressed(Key::H) {
+            state.world.cells.r[i].temperature += HEAT_STEP;
+            state.world.wake(pos.x as usize, pos.y as usize);
+        }
+        if input.pressed(Key::C) {
+            state.world.cells.r[i].temperature -= HEAT_STEP;
+            state.world.wake(pos.x as usize, pos.y as usize);
         }
     }
 
     if input.mouse_pressed(winit::event::MouseButton::Left) {
-        if let Some(pos) = cursor_grid_pos {
-            let i = pos.index(W as u32) as usize;
-            state.world.cells.r[i] = get_element!(ElementType::Sand);
-        }
+        place(state, cursor_grid_pos, "Sand");
     }
     if input.mouse_pressed(winit::event::MouseButton::Right) {
-        if let Some(pos) = cursor_grid_pos {
-            let i = pos.index(W as u32) as usize;
-            state.world.cells.r[i] = get_element!(ElementType::Stone);
-        }
+        place(state, cursor_grid_pos, "Stone");
         // if let Some(pos) = cursor_grid_pos {
         //     let i = pos.index(W as u32) as usize;
         //     state.world.cells.r[i] = Element {
@@ -105,48 +170,71 @@ pub fn update(
         //     };
         // }
     }
-    // if input.just_pressed(Key::Left) {
-    //     if let Some(pos) = cursor_grid_pos {
-    //         let i = pos.index(state.world.width() as u32) as usize;
-    //         state.world.nW[i] += 10.0;
-    //     }
-    // }
-    // if input.just_pressed(Key::Right) {
-    //     if let Some(pos) = cursor_grid_pos {
-    //         let i = pos.index(state.world.width() as u32) as usize;
-    //         state.world.ux[i] = 1.0;
-    //         state.world.uy[i] = 0.0;
-    //     }
-    // }
-    // if input.just_pressed(Key::Up) {
-    //     if let Some(pos) = cursor_grid_pos {
-    //         let i = pos.index(state.world.width() as u32) as usize;
-    //         state.world.uy[i] = 1.0;
-    //         state.world.ux[i] = 0.0;
-    //     }
-    // }
-    // if input.just_pressed(Key::Down) {
-    //     if let Some(pos) = cursor_grid_pos {
-    //         let i = pos.index(state.world.width() as u32) as usize;
-    //         state.world.uy[i] = -1.0;
-    //         state.world.ux[i] = 0.0;
-    //     }
-    // }
+    if let Some(pos) = cursor_grid_pos {
+        let i = pos.index(width as u32) as usize;
+        let fling = [
+            (Key::Left, [-FLING_SPEED, 0.0]),
+            (Key::Right, [FLING_SPEED, 0.0]),
+            (Key::Up, [0.0, FLING_SPEED]),
+            (Key::Down, [0.0, -FLING_SPEED]),
+        ];
+        for (key, velocity) in fling {
+            if input.just_pressed(key) {
+                state.world.cells.r[i].set_velocity(velocity);
+                state.world.wake(pos.x as usize, pos.y as usize);
+            }
+        }
+    }
+
+    // the blower pushes wind the way the cursor moves while G is held
+    if input.pressed(Key::G) {
+        let motion = cursor_pos - state.prev_cursor;
+        state.world.wind.blow(
+            cursor_pos.x,
+            cursor_pos.y,
+            BLOWER_RADIUS,
+            [motion.x * BLOWER_STRENGTH, motion.y * BLOWER_STRENGTH],
+        );
+    }
+    state.prev_cursor = cursor_pos;
+
+    if input.just_pressed(Key::F3) {
+        state.show_active = !state.show_active;
+    }
+
+    // Tab cycles through the debug views, which redraws every cell
+    if input.just_pressed(Key::Tab) {
+        state.view = state.view.next();
+        state.stale.push(Rect {
+            x0: 0,
+            y0: 0,
+            x1: width,
+            y1: state.world.height,
+        });
+    }
 
     if input.just_pressed(Key::T) {
         // println!("all {}", state.world.cells..sum::<f32>());
         if let Some(pos) = cursor_grid_pos {
+            let cell = state.world.cells.r[pos.y as usize * width + pos.x as usize];
             println!(
-                "cursor {:?}, pos: {:?}",
-                state.world.cells.r[pos.y as usize * W + pos.x as usize],
-                pos,
+                "cursor {} {:?}, pos: {:?}",
+                state.world.elements[cell.id].name, cell, pos,
             );
         }
     }
 
+    state.defs.update(&mut state.world);
+
     // if input.just_pressed(Key::C) || false {
     state.world.update(t_delta.as_secs_f32());
     // }
+    if let Some(recorder) = &mut state.recording {
+        if let Err(err) = recorder.record(&state.world) {
+            eprintln!("Recording stopped: {err}");
+            state.recording = None;
+        }
+    }
 
     // if let Some(pos) = cursor_grid_pos {
     //     if input.pressed(Key::B) {
@@ -158,40 +246,54 @@ pub fn update(
     //         state.world.barrier[i] = false;
     //     }
     // }
-    fn convert_color(color: u32) -> (f32, f32, f32) {
-        let red = ((color >> 16) & 0xFF) as f32 / 255.0;
-        let green = ((color >> 8) & 0xFF) as f32 / 255.0;
-        let blue = (color & 0xFF) as f32 / 255.0;
-
-        (red, green, blue)
-    }
-
-    let mut buf = vec![0; super::world::H * super::world::W];
-    state.world.render_to(&mut buf);
-    for (i, col) in buf.iter().enumerate() {
-        let color = convert_color(*col);
-        state.grid[i] = TileInstance {
-            r: color.0,
-            g: color.1,
-            b: color.2,
-            a: 1.0,
-        };
-        // } else {
-        //     match state.mouse_mode {
-        //         MouseMode::Dens => TileInstance {
-        //             r: 0.0,
-        //             g: 0.0,
-        //             b: (*dens - 0.08) * 10.0,
-        //             a: 0.0,
-        //         },
-        //         MouseMode::Vel => TileInstance {
-        //             r: (state.world.ux[i] * 5.0 + 1.0) / 2.0,
-        //             g: (state.world.uy[i] * 5.0 + 1.0) / 2.0,
-        //             b: 0.0,
-        //             a: 0.0,
-        //         }
-        //     }
-        // }
+    // redraw what the tick may have changed, and what was only right for
+    // last frame
+    state.damage.clear();
+    state.damage.extend(state.world.changed_chunks());
+    state.damage.append(&mut state.stale);
+    for &rect in &state.damage {
+        state.view.render_rect_to(&state.world, rect, &mut state.grid);
+    }
+    // changes are only marked for the frame they happen in
+    if state.view == View::Changed {
+        state.stale.extend_from_slice(&state.damage);
+    }
+
+    let legend = state.view.legend(&state.world);
+    state.legend_dirty = legend != state.legend;
+    if state.legend_dirty {
+        state.legend_image =
+            (state.view != View::Normal).then(|| Image::of_legend(&legend, LEGEND_SCALE));
+        state.legend = legend;
+    }
+
+    // outline the awake cells of each chunk, which all changed chunks cover
+    if state.show_active {
+        let outlined = state.stale.len();
+        state.stale.extend(state.world.active_chunks());
+        for &rect in &state.stale[outlined..] {
+            for y in rect.y0..rect.y1 {
+                for x in rect.x0..rect.x1 {
+                    let edge =
+                        x == rect.x0 || x + 1 == rect.x1 || y == rect.y0 || y + 1 == rect.y1;
+                    if edge {
+                        state.grid[y * width + x] = ACTIVE_COLOR;
+                    }
+                }
+            }
+        }
+    }
+
+    if input.just_pressed(Key::F12) {
+        screenshot(state);
+    }
+    // F10 records a GIF and F11 a PNG sequence of what's on screen
+    let size = renderer.window.inner_size();
+    if input.just_pressed(Key::F10) {
+        toggle_recording(state, &size, true);
+    }
+    if input.just_pressed(Key::F11) {
+        toggle_recording(state, &size, false);
     }
 
     // if let Some(pos) = cursor_grid_pos {
@@ -203,3 +305,85 @@ pub fn update(
 
     false
 }
+
+/// Saves the world as drawn, with the overlays shown on screen, to a PNG
+/// file in [`SCREENSHOT_DIR`].
+fn screenshot(state: &ClientState) {
+    let image = Image::of_world(&state.world, SCREENSHOT_SCALE, state.view, state.overlays());
+    let path = Path::new(SCREENSHOT_DIR).join(format!("{}.png", timestamp()));
+    let saved = fs::create_dir_all(SCREENSHOT_DIR)
+        .map_err(ImageError::Io)
+        .and_then(|()| image.save_png(&path));
+    match saved {
+        Ok(()) => println!("Saved {}", path.display()),
+        Err(err) => eprintln!("{}: {err}", path.display()),
+    }
+}
+
+/// Stops the running recording, or starts one of the cells on screen into
+/// [`RECORDING_DIR`], as a GIF or a sequence of PNG files.
+fn toggle_recording(state: &mut ClientState, size: &PhysicalSize<u32>, gif: bool) {
+    if let Some(recorder) = state.recording.take() {
+        match recorder.finish() {
+            Ok(frames) => println!("Recorded {frames} frames"),
+            Err(err) => eprintln!("Recording failed: {err}"),
+        }
+        return;
+    }
+
+    let name = if gif {
+        format!("{}.gif", timestamp())
+    } else {
+        timestamp().to_string()
+    };
+    let path = Path::new(RECORDING_DIR).join(name);
+    let options = RecordOptions {
+        every: RECORD_EVERY,
+        scale: RECORD_SCALE,
+        crop: visible_cells(state, size),
+        view: state.view,
+        overlays: state.overlays(),
+        ticks_per_second: FPS,
+    };
+    match Recorder::new(&path, options) {
+        Ok(recorder) => {
+            println!("Recording to {}", path.display());
+            state.recording = Some(recorder);
+        }
+        Err(err) => eprintln!("{}: {err}", path.display()),
+    }
+}
+
+/// The cells inside the window, or `None` for all of them if the window
+/// shows the whole world or none of it.
+fn visible_cells(state: &ClientState, size: &PhysicalSize<u32>) -> Option<Rect> {
+    let camera = &state.camera;
+    let top_left = camera.cursor_world_pos(Point::zero(), size);
+    let corner = Point::new(size.width as f32, size.height as f32);
+    let bottom_right = camera.cursor_world_pos(corner, size);
+    let (w, h) = (state.world.width, state.world.height);
+    let clamp = |v: f32, max: usize| (v.max(0.0) as usize).min(max);
+    let rect = Rect {
+        x0: clamp(top_left.x.floor(), w),
+        y0: clamp(bottom_right.y.floor(), h),
+        x1: clamp(bottom_right.x.ceil(), w),
+        y1: clamp(top_left.y.ceil(), h),
+    };
+    let whole = (rect.x0, rect.y0, rect.x1, rect.y1) == (0, 0, w, h);
+    (!rect.is_empty() && !whole).then_some(rect)
+}
+
+/// Milliseconds since the Unix epoch, to name saved files.
+fn timestamp() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |t| t.as_millis())
+}
+
+fn place(state: &mut ClientState, pos: Option<Point<u32>>, name: &str) {
+    if let (Some(pos), Some(id)) = (pos, state.world.elements.id(name)) {
+        let i = pos.index(state.world.width as u32) as usize;
+        state.world.cells.r[i] = state.world.create(id);
+        state.world.wake(pos.x as usize, pos.y as usize);
+    }
+}
@@ -1,5 +1,4 @@
 mod client;
-mod util;
 
 fn main() {
     pollster::block_on(client::run_client());